@@ -3,32 +3,25 @@
 //! Read lines from `stdin` in a streaming fashion and populate the picker, imitating the basic
 //! functionality of [fzf](https://github.com/junegunn/fzf).
 use std::{
-    io::{self, IsTerminal},
+    io::{self, BufReader, IsTerminal},
     process::exit,
-    thread::spawn,
 };
 
-use nucleo_picker::{render::StrRenderer, Picker};
+use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
 
 fn main() -> io::Result<()> {
-    let mut picker = Picker::new(StrRenderer);
+    // print the selected line to stdout when the picker closes
+    let mut picker: Picker<String, _> =
+        PickerOptions::new().print_on_exit(true).picker(StrRenderer);
 
-    let injector = picker.injector();
-    spawn(move || {
-        let stdin = io::stdin();
-        if !stdin.is_terminal() {
-            for line in stdin.lines() {
-                // silently drop IO errors!
-                if let Ok(s) = line {
-                    injector.push(s);
-                }
-            }
-        }
-    });
+    // read the items from stdin in a background thread, unless stdin is interactive
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        picker.injector().spawn_lines(BufReader::new(stdin));
+    }
 
-    match picker.pick()? {
-        Some(it) => println!("{it}"),
-        None => exit(1),
+    if picker.pick()?.is_none() {
+        exit(1);
     }
     Ok(())
 }
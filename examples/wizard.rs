@@ -0,0 +1,47 @@
+//! # Multi-step selection
+//!
+//! This blocking example asks for a category, and then for an item in that category, keeping the
+//! terminal on the alternate screen between the two steps so that the screen does not flash.
+use std::io::Result;
+
+use nucleo_picker::{render::StrRenderer, Picker, Selection, Target, TerminalGuard};
+
+fn main() -> Result<()> {
+    let mut picker: Picker<&str, _> = Picker::new(StrRenderer);
+
+    // the terminal is restored when the guard is dropped
+    let mut guard = TerminalGuard::new(Target::Stderr)?;
+
+    // first step: pick a category
+    picker.extend(["fruit", "vegetable"]);
+    let Some(category) = picker
+        .pick_in(&mut guard)?
+        .selection
+        .and_then(Selection::into_pick)
+        .map(|pick| *pick.item())
+    else {
+        drop(guard);
+        println!("Nothing selected!");
+        return Ok(());
+    };
+
+    // second step: replace the items and pick an item in the category
+    picker.restart();
+    match category {
+        "fruit" => picker.extend(["apple", "banana", "cherry"]),
+        _ => picker.extend(["carrot", "leek", "potato"]),
+    }
+    let choice = picker
+        .pick_in(&mut guard)?
+        .selection
+        .and_then(Selection::into_pick)
+        .map(|pick| *pick.item());
+
+    drop(guard);
+    match choice {
+        Some(item) => println!("You selected the {category} '{item}'"),
+        None => println!("Nothing selected!"),
+    }
+
+    Ok(())
+}
@@ -1,18 +1,36 @@
 //! # Event handling and keybind definitions
 //! In this module, we define the key bindings used by the TUI and also handle other events.
-//! Internally, we represent an event as an [`Event`]. To handle this, we convert from
-//! [`crossterm::event::Event`] with the [`convert`] method.
-use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+//!
+//! Key bindings are represented by a [`KeyBindings`] table, which maps keys to an [`Action`]. A
+//! table can be built from string specifications such as `"ctrl-k"` and `"move-up"`, and (with
+//! the `serde` feature) deserialized from a configuration file. The table is then passed to the
+//! picker using [`PickerOptions::keybindings`](crate::PickerOptions::keybindings).
+//!
+//! Internally, we represent an event as an `Event`. To handle this, we convert from
+//! [`crossterm::event::Event`] with the `convert` method.
+use std::{collections::HashMap, error::Error, fmt, str::FromStr};
+
+use crossterm::event::{
+    Event as CrosstermEvent, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton,
+    MouseEvent, MouseEventKind,
+};
+
+/// The number of columns scrolled by the `scroll-left` and `scroll-right` actions.
+const SCROLL_COLUMNS: u16 = 8;
 
 /// A possible action that a component might handle.
 #[derive(Debug, PartialEq, Eq)]
-pub enum Event {
+pub(crate) enum Event {
     MoveLeft,
     MoveWordLeft,
     MoveRight,
     MoveWordRight,
     MoveUp,
     MoveDown,
+    MoveToFirst,
+    MoveToLast,
+    ScrollLeft(u16),
+    ScrollRight(u16),
     MoveToStart,
     MoveToEnd,
     Backspace,
@@ -20,77 +38,547 @@ pub enum Event {
     ClearBefore,
     Delete,
     ClearAfter,
+    Yank,
+    YankPop,
+    Undo,
+    Redo,
     Quit,
     QuitIfEmpty,
     Abort,
     Resize(u16, u16),
     Insert(char),
     Select,
+    SelectOrQuery,
+    AcceptQuery,
+    Expect(String),
     Paste(String),
+    Click(u16),
+    ToggleCaseMatching,
+    ToggleExact,
+    Reload,
+}
+
+/// An action which can be bound to a key.
+///
+/// The string representation of an action, used when parsing or deserializing a
+/// [`KeyBindings`] table, is given in brackets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Action {
+    /// Move the prompt cursor left by one character (`move-left`).
+    MoveLeft,
+    /// Move the prompt cursor left by one word (`move-word-left`).
+    MoveWordLeft,
+    /// Move the prompt cursor right by one character (`move-right`).
+    MoveRight,
+    /// Move the prompt cursor right by one word (`move-word-right`).
+    MoveWordRight,
+    /// Move the selection up in the match list (`move-up`).
+    MoveUp,
+    /// Move the selection down in the match list (`move-down`).
+    MoveDown,
+    /// Move the selection to the first match, which is the best match at the bottom of the match
+    /// list (`move-to-first`). Not bound by default.
+    MoveToFirst,
+    /// Move the selection to the last match, which is the worst match at the top of the match
+    /// list (`move-to-last`). Not bound by default.
+    MoveToLast,
+    /// Scroll the matches left by eight columns (`scroll-left`). Not bound by default.
+    ScrollLeft,
+    /// Scroll the matches right by eight columns, to inspect the parts of long items which do
+    /// not fit on the screen (`scroll-right`). Every item is scrolled by the same amount, but
+    /// never so far that its end is no longer visible. Not bound by default.
+    ScrollRight,
+    /// Move the prompt cursor to the start of the prompt (`move-to-start`).
+    MoveToStart,
+    /// Move the prompt cursor to the end of the prompt (`move-to-end`).
+    MoveToEnd,
+    /// Delete the character before the prompt cursor (`backspace`).
+    Backspace,
+    /// Delete the word before the prompt cursor (`backspace-word`).
+    BackspaceWord,
+    /// Delete everything before the prompt cursor (`clear-before`).
+    ClearBefore,
+    /// Delete the character after the prompt cursor (`delete`).
+    Delete,
+    /// Delete everything after the prompt cursor (`clear-after`).
+    ClearAfter,
+    /// Insert the text most recently deleted by `backspace-word`, `clear-before`, or
+    /// `clear-after` at the prompt cursor (`yank`). Consecutive deletions are inserted together.
+    Yank,
+    /// Immediately after `yank` or `yank-pop`, replace the inserted text with the text deleted
+    /// before it (`yank-pop`).
+    YankPop,
+    /// Undo the previous change to the prompt (`undo`).
+    Undo,
+    /// Redo the previously undone change to the prompt (`redo`). Not bound by default.
+    Redo,
+    /// Quit the picker without selecting an item (`quit`).
+    Quit,
+    /// Quit the picker without selecting an item if the prompt is empty (`quit-if-empty`).
+    QuitIfEmpty,
+    /// Quit the picker and return an error (`abort`).
+    Abort,
+    /// Select the current item and quit (`select`).
+    Select,
+    /// Select the current item and quit, or accept the contents of the prompt if there are no
+    /// matches (`select-or-query`). Not bound by default.
+    SelectOrQuery,
+    /// Accept the contents of the prompt instead of an item and quit, even if there are matches
+    /// (`accept-query`). Not bound by default.
+    AcceptQuery,
+    /// Select the current item, if any, and quit, recording the key which was pressed in
+    /// [`Outcome::key`](crate::Outcome::key) (`expect`). This is similar to `fzf --expect`, and
+    /// can be used to perform different operations on the selected item depending on the key.
+    /// Not bound by default.
+    Expect,
+    /// Do nothing; use this to disable a default key binding (`ignore`).
+    Ignore,
+    /// Cycle between smart case, ignoring case, and respecting case when matching
+    /// (`toggle-case`). Not bound by default.
+    ToggleCaseMatching,
+    /// Toggle between fuzzy and exact matching (`toggle-exact`). In exact mode, each word of the
+    /// query is matched as a substring, unless it begins with `'`, in which case it is matched
+    /// fuzzily. Not bound by default.
+    ToggleExact,
+    /// Remove all of the items and add them again using the producer registered with
+    /// [`Picker::set_reload`](crate::Picker::set_reload), keeping the contents of the prompt
+    /// (`reload`). This does nothing if no producer is registered. Not bound by default.
+    Reload,
+}
+
+impl Action {
+    const ALL: [(Self, &'static str); 32] = [
+        (Self::MoveLeft, "move-left"),
+        (Self::MoveWordLeft, "move-word-left"),
+        (Self::MoveRight, "move-right"),
+        (Self::MoveWordRight, "move-word-right"),
+        (Self::MoveUp, "move-up"),
+        (Self::MoveDown, "move-down"),
+        (Self::MoveToFirst, "move-to-first"),
+        (Self::MoveToLast, "move-to-last"),
+        (Self::ScrollLeft, "scroll-left"),
+        (Self::ScrollRight, "scroll-right"),
+        (Self::MoveToStart, "move-to-start"),
+        (Self::MoveToEnd, "move-to-end"),
+        (Self::Backspace, "backspace"),
+        (Self::BackspaceWord, "backspace-word"),
+        (Self::ClearBefore, "clear-before"),
+        (Self::Delete, "delete"),
+        (Self::ClearAfter, "clear-after"),
+        (Self::Yank, "yank"),
+        (Self::YankPop, "yank-pop"),
+        (Self::Undo, "undo"),
+        (Self::Redo, "redo"),
+        (Self::Quit, "quit"),
+        (Self::QuitIfEmpty, "quit-if-empty"),
+        (Self::Abort, "abort"),
+        (Self::Select, "select"),
+        (Self::SelectOrQuery, "select-or-query"),
+        (Self::AcceptQuery, "accept-query"),
+        (Self::Expect, "expect"),
+        (Self::Ignore, "ignore"),
+        (Self::ToggleCaseMatching, "toggle-case"),
+        (Self::ToggleExact, "toggle-exact"),
+        (Self::Reload, "reload"),
+    ];
+
+    /// The string representation of the action.
+    fn name(self) -> &'static str {
+        // SAFETY: every action is contained in `ALL`
+        Self::ALL
+            .iter()
+            .find(|(action, _)| *action == self)
+            .unwrap()
+            .1
+    }
+
+    /// Convert into the internal event representation.
+    fn to_event(self) -> Option<Event> {
+        match self {
+            Self::MoveLeft => Some(Event::MoveLeft),
+            Self::MoveWordLeft => Some(Event::MoveWordLeft),
+            Self::MoveRight => Some(Event::MoveRight),
+            Self::MoveWordRight => Some(Event::MoveWordRight),
+            Self::MoveUp => Some(Event::MoveUp),
+            Self::MoveDown => Some(Event::MoveDown),
+            Self::MoveToFirst => Some(Event::MoveToFirst),
+            Self::MoveToLast => Some(Event::MoveToLast),
+            Self::ScrollLeft => Some(Event::ScrollLeft(SCROLL_COLUMNS)),
+            Self::ScrollRight => Some(Event::ScrollRight(SCROLL_COLUMNS)),
+            Self::MoveToStart => Some(Event::MoveToStart),
+            Self::MoveToEnd => Some(Event::MoveToEnd),
+            Self::Backspace => Some(Event::Backspace),
+            Self::BackspaceWord => Some(Event::BackspaceWord),
+            Self::ClearBefore => Some(Event::ClearBefore),
+            Self::Delete => Some(Event::Delete),
+            Self::ClearAfter => Some(Event::ClearAfter),
+            Self::Yank => Some(Event::Yank),
+            Self::YankPop => Some(Event::YankPop),
+            Self::Undo => Some(Event::Undo),
+            Self::Redo => Some(Event::Redo),
+            Self::Quit => Some(Event::Quit),
+            Self::QuitIfEmpty => Some(Event::QuitIfEmpty),
+            Self::Abort => Some(Event::Abort),
+            Self::Select => Some(Event::Select),
+            Self::SelectOrQuery => Some(Event::SelectOrQuery),
+            Self::AcceptQuery => Some(Event::AcceptQuery),
+            // the key is only known when converting the key event
+            Self::Expect => None,
+            Self::Ignore => None,
+            Self::ToggleCaseMatching => Some(Event::ToggleCaseMatching),
+            Self::ToggleExact => Some(Event::ToggleExact),
+            Self::Reload => Some(Event::Reload),
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Action {
+    type Err = ParseBindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|(action, _)| *action)
+            .ok_or_else(|| ParseBindingError::InvalidAction(s.to_owned()))
+    }
+}
+
+/// An error returned when parsing a key or an [`Action`] from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseBindingError {
+    /// The key specification is invalid.
+    InvalidKey(String),
+    /// The action name is invalid.
+    InvalidAction(String),
+}
+
+impl fmt::Display for ParseBindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidKey(key) => write!(f, "invalid key '{key}'"),
+            Self::InvalidAction(action) => write!(f, "invalid action '{action}'"),
+        }
+    }
+}
+
+impl Error for ParseBindingError {}
+
+/// A key, along with any modifiers which must be held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    /// Normalize a key so that shifted characters are represented by the character alone, since
+    /// terminals report the shift modifier inconsistently.
+    fn normalize(mut self) -> Self {
+        if let KeyCode::Char(ch) = self.code {
+            if self.modifiers.contains(KeyModifiers::SHIFT) {
+                self.modifiers.remove(KeyModifiers::SHIFT);
+                self.code = KeyCode::Char(ch.to_ascii_uppercase());
+            }
+        }
+        self
+    }
+}
+
+impl fmt::Display for Key {
+    /// Write the key in the same format which is accepted by the [`FromStr`] implementation.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl-"),
+            (KeyModifiers::ALT, "alt-"),
+            (KeyModifiers::SHIFT, "shift-"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(ch) => write!(f, "{ch}"),
+            KeyCode::Enter => f.write_str("enter"),
+            KeyCode::Esc => f.write_str("esc"),
+            KeyCode::Tab => f.write_str("tab"),
+            KeyCode::BackTab => f.write_str("backtab"),
+            KeyCode::Backspace => f.write_str("backspace"),
+            KeyCode::Delete => f.write_str("delete"),
+            KeyCode::Insert => f.write_str("insert"),
+            KeyCode::Up => f.write_str("up"),
+            KeyCode::Down => f.write_str("down"),
+            KeyCode::Left => f.write_str("left"),
+            KeyCode::Right => f.write_str("right"),
+            KeyCode::Home => f.write_str("home"),
+            KeyCode::End => f.write_str("end"),
+            KeyCode::PageUp => f.write_str("pageup"),
+            KeyCode::PageDown => f.write_str("pagedown"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            // keys which cannot be parsed are never bound
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = ParseBindingError;
+
+    /// Parse a key specification, such as `ctrl-k`, `alt-enter`, or `a`. The key name is the final
+    /// component, and any preceding components are modifiers.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseBindingError::InvalidKey(s.to_owned());
+
+        // a trailing '-' is the key itself, e.g. `ctrl--`
+        let (modifier_spec, name) = match s.strip_suffix("--") {
+            Some(prefix) => (Some(prefix), "-"),
+            None => match s.rsplit_once('-') {
+                Some(("", "")) => (None, s),
+                Some((prefix, name)) if !name.is_empty() => (Some(prefix), name),
+                Some(_) => return Err(invalid()),
+                None => (None, s),
+            },
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in modifier_spec.into_iter().flat_map(|spec| spec.split('-')) {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            };
+        }
+
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(ch), None) => KeyCode::Char(ch),
+            _ => match name.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" | "bspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                lower => match lower.strip_prefix('f').map(str::parse) {
+                    Some(Ok(n @ 1..=24)) => KeyCode::F(n),
+                    _ => return Err(invalid()),
+                },
+            },
+        };
+
+        Ok(Self::new(code, modifiers).normalize())
+    }
+}
+
+/// A table of key bindings.
+///
+/// The [`Default`] implementation contains the default key bindings of the picker. Bindings can
+/// be added or overridden using [`bind`](KeyBindings::bind) and
+/// [`bind_str`](KeyBindings::bind_str). Characters which are not bound to an action are inserted
+/// into the prompt.
+///
+/// A key is specified as a key name, optionally preceded by modifiers separated by `-`, such as
+/// `ctrl-k` or `alt-shift-left`. The key name is either a single character, or one of `enter`,
+/// `esc`, `tab`, `backtab`, `space`, `backspace`, `delete`, `insert`, `up`, `down`, `left`,
+/// `right`, `home`, `end`, `pageup`, `pagedown`, or `f1` through `f24`. The supported modifiers
+/// are `ctrl`, `alt`, and `shift`. The string representation of each action is documented in
+/// [`Action`].
+///
+/// ## Example
+/// ```
+/// use nucleo_picker::{
+///     bind::{Action, KeyBindings},
+///     render::StrRenderer,
+///     Picker, PickerOptions,
+/// };
+///
+/// let mut keybindings = KeyBindings::default();
+/// keybindings.bind_str("ctrl-k", "move-down").unwrap();
+/// keybindings.bind_str("ctrl-j", "move-up").unwrap();
+/// keybindings.bind("esc", Action::Ignore).unwrap();
+///
+/// let picker: Picker<String, _> = PickerOptions::new()
+///     .keybindings(keybindings)
+///     .picker(StrRenderer);
+/// ```
+///
+/// ### `Deserialize` implementation
+/// With the `serde` feature enabled, a table of key bindings can be deserialized from a map
+/// from keys to actions. The deserialized bindings are applied on top of the default bindings.
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # fn main() {
+/// use nucleo_picker::bind::KeyBindings;
+///
+/// let keybindings: KeyBindings = serde_json::from_str(
+///     r#"{ "ctrl-k": "move-down", "ctrl-j": "move-up", "esc": "ignore" }"#,
+/// )
+/// .unwrap();
+/// # }
+/// # #[cfg(not(feature = "serde"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    bindings: HashMap<Key, Action>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        const NONE: KeyModifiers = KeyModifiers::NONE;
+        const CONTROL: KeyModifiers = KeyModifiers::CONTROL;
+        const ALT: KeyModifiers = KeyModifiers::ALT;
+        const SHIFT: KeyModifiers = KeyModifiers::SHIFT;
+
+        let defaults = [
+            (KeyCode::Esc, NONE, Action::Quit),
+            (KeyCode::Up, NONE, Action::MoveUp),
+            (KeyCode::Down, NONE, Action::MoveDown),
+            (KeyCode::Left, NONE, Action::MoveLeft),
+            (KeyCode::Right, NONE, Action::MoveRight),
+            (KeyCode::Home, NONE, Action::MoveToStart),
+            (KeyCode::End, NONE, Action::MoveToEnd),
+            (KeyCode::Backspace, NONE, Action::Backspace),
+            (KeyCode::Enter, NONE, Action::Select),
+            (KeyCode::Delete, NONE, Action::Delete),
+            (KeyCode::Char('c'), CONTROL, Action::Abort),
+            (KeyCode::Char('d'), CONTROL, Action::QuitIfEmpty),
+            (KeyCode::Char('g'), CONTROL, Action::Quit),
+            (KeyCode::Char('q'), CONTROL, Action::Quit),
+            (KeyCode::Char('k'), CONTROL, Action::MoveUp),
+            (KeyCode::Char('p'), CONTROL, Action::MoveUp),
+            (KeyCode::Char('j'), CONTROL, Action::MoveDown),
+            (KeyCode::Char('n'), CONTROL, Action::MoveDown),
+            (KeyCode::Char('b'), CONTROL, Action::MoveLeft),
+            (KeyCode::Char('f'), CONTROL, Action::MoveRight),
+            (KeyCode::Char('a'), CONTROL, Action::MoveToStart),
+            (KeyCode::Char('e'), CONTROL, Action::MoveToEnd),
+            (KeyCode::Char('h'), CONTROL, Action::Backspace),
+            (KeyCode::Char('w'), CONTROL, Action::BackspaceWord),
+            (KeyCode::Char('u'), CONTROL, Action::ClearBefore),
+            (KeyCode::Char('o'), CONTROL, Action::ClearAfter),
+            (KeyCode::Char('y'), CONTROL, Action::Yank),
+            (KeyCode::Char('y'), ALT, Action::YankPop),
+            (KeyCode::Char('z'), CONTROL, Action::Undo),
+            (KeyCode::Char('f'), ALT, Action::MoveWordRight),
+            (KeyCode::Char('b'), ALT, Action::MoveWordLeft),
+            (KeyCode::Backspace, SHIFT, Action::Backspace),
+            (KeyCode::Enter, SHIFT, Action::Select),
+        ];
+
+        Self {
+            bindings: defaults
+                .into_iter()
+                .map(|(code, modifiers, action)| (Key::new(code, modifiers), action))
+                .collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// A table with no key bindings. Characters are still inserted into the prompt.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+
+    /// Bind the key to the given action, replacing any previous binding for the key.
+    ///
+    /// # Errors
+    /// Returns an error if the key specification is invalid.
+    pub fn bind(&mut self, key: &str, action: Action) -> Result<(), ParseBindingError> {
+        self.bindings.insert(key.parse()?, action);
+        Ok(())
+    }
+
+    /// Bind the key to the action with the given name, replacing any previous binding for the
+    /// key.
+    ///
+    /// # Errors
+    /// Returns an error if the key specification or the action name is invalid.
+    pub fn bind_str(&mut self, key: &str, action: &str) -> Result<(), ParseBindingError> {
+        self.bind(key, action.parse()?)
+    }
+
+    /// Remove the binding for the key, returning the action which was previously bound.
+    ///
+    /// # Errors
+    /// Returns an error if the key specification is invalid.
+    pub fn unbind(&mut self, key: &str) -> Result<Option<Action>, ParseBindingError> {
+        Ok(self.bindings.remove(&key.parse()?))
+    }
+
+    /// Get the action bound to the key, if any.
+    ///
+    /// # Errors
+    /// Returns an error if the key specification is invalid.
+    pub fn get(&self, key: &str) -> Result<Option<Action>, ParseBindingError> {
+        Ok(self.bindings.get(&key.parse()?).copied())
+    }
+
+    /// Look up the action bound to a key event.
+    fn lookup(&self, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        self.bindings
+            .get(&Key::new(code, modifiers).normalize())
+            .copied()
+    }
 }
 
 /// Convert any [`crossterm::event::Event`] that we handle.
-pub fn convert(event: CrosstermEvent) -> Option<Event> {
+pub(crate) fn convert(event: CrosstermEvent, keybindings: &KeyBindings) -> Option<Event> {
     match event {
         CrosstermEvent::Key(KeyEvent {
             kind: KeyEventKind::Press,
-            modifiers: KeyModifiers::NONE,
-            code,
-            ..
-        }) => match code {
-            KeyCode::Esc => Some(Event::Quit),
-            KeyCode::Up => Some(Event::MoveUp),
-            KeyCode::Down => Some(Event::MoveDown),
-            KeyCode::Left => Some(Event::MoveLeft),
-            KeyCode::Right => Some(Event::MoveRight),
-            KeyCode::Home => Some(Event::MoveToStart),
-            KeyCode::End => Some(Event::MoveToEnd),
-            KeyCode::Char(ch) => Some(Event::Insert(ch)),
-            KeyCode::Backspace => Some(Event::Backspace),
-            KeyCode::Enter => Some(Event::Select),
-            KeyCode::Delete => Some(Event::Delete),
-            _ => None,
-        },
-        CrosstermEvent::Key(KeyEvent {
-            kind: KeyEventKind::Press,
-            modifiers: KeyModifiers::CONTROL,
-            code,
-            ..
-        }) => match code {
-            KeyCode::Char('c') => Some(Event::Abort),
-            KeyCode::Char('d') => Some(Event::QuitIfEmpty),
-            KeyCode::Char('g' | 'q') => Some(Event::Quit),
-            KeyCode::Char('k' | 'p') => Some(Event::MoveUp),
-            KeyCode::Char('j' | 'n') => Some(Event::MoveDown),
-            KeyCode::Char('b') => Some(Event::MoveLeft),
-            KeyCode::Char('f') => Some(Event::MoveRight),
-            KeyCode::Char('a') => Some(Event::MoveToStart),
-            KeyCode::Char('e') => Some(Event::MoveToEnd),
-            KeyCode::Char('h') => Some(Event::Backspace),
-            KeyCode::Char('w') => Some(Event::BackspaceWord),
-            KeyCode::Char('u') => Some(Event::ClearBefore),
-            KeyCode::Char('o') => Some(Event::ClearAfter),
-            _ => None,
-        },
-        CrosstermEvent::Key(KeyEvent {
-            kind: KeyEventKind::Press,
-            modifiers: KeyModifiers::ALT,
+            modifiers,
             code,
             ..
-        }) => match code {
-            KeyCode::Char('f') => Some(Event::MoveWordRight),
-            KeyCode::Char('b') => Some(Event::MoveWordLeft),
-            _ => None,
+        }) => match keybindings.lookup(code, modifiers) {
+            Some(Action::Expect) => Some(Event::Expect(
+                Key::new(code, modifiers).normalize().to_string(),
+            )),
+            Some(action) => action.to_event(),
+            None => match code {
+                KeyCode::Char(ch)
+                    if modifiers == KeyModifiers::NONE || modifiers == KeyModifiers::SHIFT =>
+                {
+                    Some(Event::Insert(ch))
+                }
+                _ => None,
+            },
         },
-        CrosstermEvent::Key(KeyEvent {
-            kind: KeyEventKind::Press,
-            modifiers: KeyModifiers::SHIFT,
-            code,
-            ..
-        }) => match code {
-            KeyCode::Char(ch) => Some(Event::Insert(ch)),
-            KeyCode::Backspace => Some(Event::Backspace),
-            KeyCode::Enter => Some(Event::Select),
+        CrosstermEvent::Mouse(MouseEvent { kind, row, .. }) => match kind {
+            MouseEventKind::ScrollUp => Some(Event::MoveUp),
+            MouseEventKind::ScrollDown => Some(Event::MoveDown),
+            MouseEventKind::ScrollLeft => Some(Event::ScrollLeft(1)),
+            MouseEventKind::ScrollRight => Some(Event::ScrollRight(1)),
+            MouseEventKind::Down(MouseButton::Left) => Some(Event::Click(row)),
             _ => None,
         },
         CrosstermEvent::Resize(width, height) => Some(Event::Resize(width, height)),
@@ -98,3 +586,151 @@ pub fn convert(event: CrosstermEvent) -> Option<Event> {
         _ => None,
     }
 }
+
+#[cfg(feature = "serde")]
+mod serde {
+    use std::fmt;
+
+    use serde::{
+        de::{Error, MapAccess, Visitor},
+        Deserialize, Deserializer,
+    };
+
+    use super::KeyBindings;
+
+    struct KeyBindingsVisitor;
+
+    impl<'de> Visitor<'de> for KeyBindingsVisitor {
+        type Value = KeyBindings;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map from keys to actions")
+        }
+
+        fn visit_map<M>(self, mut map: M) -> Result<KeyBindings, M::Error>
+        where
+            M: MapAccess<'de>,
+        {
+            let mut keybindings = KeyBindings::default();
+            while let Some((key, action)) = map.next_entry::<String, String>()? {
+                keybindings
+                    .bind_str(&key, &action)
+                    .map_err(M::Error::custom)?;
+            }
+            Ok(keybindings)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for KeyBindings {
+        /// Deserialize from a map from keys to actions, applied on top of the default bindings.
+        /// This implementation is enabled using the `serde` feature.
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_map(KeyBindingsVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key() {
+        fn assert_key(spec: &str, code: KeyCode, modifiers: KeyModifiers) {
+            assert_eq!(spec.parse::<Key>(), Ok(Key::new(code, modifiers)));
+        }
+
+        assert_key("a", KeyCode::Char('a'), KeyModifiers::NONE);
+        assert_key("A", KeyCode::Char('A'), KeyModifiers::NONE);
+        assert_key("shift-a", KeyCode::Char('A'), KeyModifiers::NONE);
+        assert_key("-", KeyCode::Char('-'), KeyModifiers::NONE);
+        assert_key("ctrl--", KeyCode::Char('-'), KeyModifiers::CONTROL);
+        assert_key("ctrl-k", KeyCode::Char('k'), KeyModifiers::CONTROL);
+        assert_key(
+            "ctrl-alt-x",
+            KeyCode::Char('x'),
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+        );
+        assert_key("enter", KeyCode::Enter, KeyModifiers::NONE);
+        assert_key("shift-Enter", KeyCode::Enter, KeyModifiers::SHIFT);
+        assert_key("space", KeyCode::Char(' '), KeyModifiers::NONE);
+        assert_key("f12", KeyCode::F(12), KeyModifiers::NONE);
+
+        // keys are displayed in the same format
+        for spec in [
+            "a",
+            "A",
+            "-",
+            "ctrl--",
+            "ctrl-k",
+            "ctrl-alt-x",
+            "shift-enter",
+            "space",
+        ] {
+            assert_eq!(spec.parse::<Key>().unwrap().to_string(), spec);
+        }
+        assert_eq!("shift-a".parse::<Key>().unwrap().to_string(), "A");
+        assert_eq!(
+            "alt-ctrl-Enter".parse::<Key>().unwrap().to_string(),
+            "ctrl-alt-enter"
+        );
+
+        for invalid in ["", "ctrl-", "super-a", "f0", "f25", "enterr", "ctrl-foo"] {
+            assert_eq!(
+                invalid.parse::<Key>(),
+                Err(ParseBindingError::InvalidKey(invalid.to_owned()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_action() {
+        for (action, name) in Action::ALL {
+            assert_eq!(name.parse::<Action>(), Ok(action));
+            assert_eq!(action.to_string(), name);
+        }
+        assert!("match-list-up".parse::<Action>().is_err());
+    }
+
+    #[test]
+    fn test_convert() {
+        fn press(code: KeyCode, modifiers: KeyModifiers) -> CrosstermEvent {
+            CrosstermEvent::Key(KeyEvent::new(code, modifiers))
+        }
+
+        let mut keybindings = KeyBindings::default();
+        assert_eq!(
+            convert(
+                press(KeyCode::Char('k'), KeyModifiers::CONTROL),
+                &keybindings
+            ),
+            Some(Event::MoveUp)
+        );
+        assert_eq!(
+            convert(press(KeyCode::Char('K'), KeyModifiers::SHIFT), &keybindings),
+            Some(Event::Insert('K'))
+        );
+
+        keybindings.bind_str("ctrl-k", "move-down").unwrap();
+        keybindings.bind_str("K", "select").unwrap();
+        keybindings.bind("esc", Action::Ignore).unwrap();
+        assert_eq!(
+            convert(
+                press(KeyCode::Char('k'), KeyModifiers::CONTROL),
+                &keybindings
+            ),
+            Some(Event::MoveDown)
+        );
+        assert_eq!(
+            convert(press(KeyCode::Char('K'), KeyModifiers::SHIFT), &keybindings),
+            Some(Event::Select)
+        );
+        assert_eq!(
+            convert(press(KeyCode::Esc, KeyModifiers::NONE), &keybindings),
+            None
+        );
+    }
+}
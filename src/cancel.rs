@@ -0,0 +1,128 @@
+use std::sync::{
+    atomic::{AtomicU64, AtomicU8, Ordering},
+    Arc,
+};
+
+/// The picker is not open, so cancelling has no effect.
+const CLOSED: u8 = 0;
+/// The picker is open.
+const OPEN: u8 = 1;
+/// The picker is open and was cancelled.
+const CANCELLED: u8 = 2;
+
+/// The cancellation state shared between a [`Picker`](super::Picker), its handles, and its
+/// injectors.
+#[derive(Debug, Default)]
+pub(crate) struct CancelState {
+    state: AtomicU8,
+    /// The number of times the picker was closed.
+    closed: AtomicU64,
+}
+
+impl CancelState {
+    /// Record that the picker was opened, discarding any previous cancellation. The picker is
+    /// closed when the returned guard is dropped.
+    pub fn open(self: &Arc<Self>) -> OpenGuard {
+        self.state.store(OPEN, Ordering::Release);
+        OpenGuard(self.clone())
+    }
+
+    /// Whether or not the open picker was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.load(Ordering::Acquire) == CANCELLED
+    }
+
+    /// The number of times the picker was closed.
+    pub fn closed_count(&self) -> u64 {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    /// Cancel the picker, if it is open.
+    fn cancel(&self) {
+        // a failed exchange means that the picker is not open, or was already cancelled
+        let _ = self
+            .state
+            .compare_exchange(OPEN, CANCELLED, Ordering::AcqRel, Ordering::Acquire);
+    }
+}
+
+/// A guard which records that the picker was closed when it is dropped, including when the
+/// picker returns early with an error.
+pub(crate) struct OpenGuard(Arc<CancelState>);
+
+impl Drop for OpenGuard {
+    fn drop(&mut self) {
+        self.0.state.store(CLOSED, Ordering::Release);
+        self.0.closed.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// A handle which allows closing an interactive [`Picker`](super::Picker) from another thread.
+///
+/// This struct is cheaply clonable and can be sent across threads. Obtain a handle with
+/// [`Picker::cancel_handle`](super::Picker::cancel_handle), and call
+/// [`cancel`](CancelHandle::cancel) while the picker is open to close it. The picker then
+/// restores the terminal and returns an [`io::Error`](std::io::Error) containing
+/// [`PickError::Cancelled`](super::PickError::Cancelled), with the message `"cancelled"`.
+///
+/// ## Example
+/// ```no_run
+/// use std::{thread, time::Duration};
+///
+/// use nucleo_picker::{render::StrRenderer, Picker};
+///
+/// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+/// let handle = picker.cancel_handle();
+///
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_secs(10));
+///     handle.cancel();
+/// });
+///
+/// match picker.pick() {
+///     Ok(choice) => println!("{choice:?}"),
+///     Err(err) => eprintln!("{err}"),
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CancelHandle {
+    state: Arc<CancelState>,
+}
+
+impl CancelHandle {
+    pub(crate) fn new(state: Arc<CancelState>) -> Self {
+        Self { state }
+    }
+
+    /// Close the picker. This has no effect if the picker is not currently open, so it does not
+    /// affect a later call to one of the pick methods.
+    pub fn cancel(&self) {
+        self.state.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_before_pick() {
+        let state = Arc::new(CancelState::default());
+        let handle = CancelHandle::new(state.clone());
+
+        // cancelling before the picker is opened has no effect
+        handle.cancel();
+        let guard = state.open();
+        assert!(!state.is_cancelled());
+
+        handle.cancel();
+        assert!(state.is_cancelled());
+
+        // cancelling after the picker is closed does not affect the next pick
+        drop(guard);
+        assert_eq!(state.closed_count(), 1);
+        handle.cancel();
+        let _guard = state.open();
+        assert!(!state.is_cancelled());
+    }
+}
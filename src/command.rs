@@ -0,0 +1,299 @@
+//! # Populate a [`Picker`](super::Picker) with the output of a command
+//!
+//! This module contains a [`CommandInjector`], which runs an external command and adds each line
+//! written by the command to its standard output to a picker. The command is killed when the
+//! picker no longer accepts items or when the interactive picker is closed, so it can be used
+//! together with
+//! [`Picker::set_reload`](super::Picker::set_reload) or
+//! [`Picker::set_query_source`](super::Picker::set_query_source) to rerun a command without
+//! leaving the previous command running in the background.
+use std::{
+    ffi::OsStr,
+    io::{self, BufReader},
+    process::{Command, ExitStatus, Stdio},
+    thread::{self, sleep, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use super::{CancelHandle, Injector, Render};
+
+/// How often to check whether the injector was disconnected while the command is running.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How long to wait for the remaining output after the command exits.
+const READ_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Run a command on a background thread, and add each line of its output to a picker.
+///
+/// The standard output of the command is read line by line as with
+/// [`Injector::spawn_lines`], and the standard input of the command is closed. The standard error
+/// is inherited by default, so it is recommended to redirect it using
+/// [`command`](CommandInjector::command) since anything written to it is drawn over the picker.
+///
+/// The command is killed if the injector is disconnected from the picker, for instance if the
+/// picker is restarted with [`Picker::restart`](super::Picker::restart) or dropped. The command
+/// is also killed when the interactive picker is closed, if the command was started before or
+/// while the picker was open.
+///
+/// ## Example
+/// ```no_run
+/// use std::process::Stdio;
+///
+/// use nucleo_picker::{
+///     bind::KeyBindings, command::CommandInjector, render::StrRenderer, Picker, PickerOptions,
+/// };
+///
+/// let mut keybindings = KeyBindings::default();
+/// keybindings.bind_str("ctrl-r", "reload").unwrap();
+///
+/// let mut picker: Picker<String, _> = PickerOptions::new()
+///     .keybindings(keybindings)
+///     .picker(StrRenderer);
+/// let cancel = picker.cancel_handle();
+///
+/// // list the files tracked by git, and rerun the command when `ctrl-r` is pressed
+/// picker.set_reload(move |injector| {
+///     let mut git = CommandInjector::new("git");
+///     git.command().arg("ls-files").stderr(Stdio::null());
+///     git.abort_on_failure(cancel.clone()).spawn(injector);
+/// });
+/// picker.reload();
+///
+/// if let Some(path) = picker.pick()? {
+///     println!("{path}");
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct CommandInjector {
+    command: Command,
+    abort: Option<CancelHandle>,
+}
+
+impl CommandInjector {
+    /// Run the provided program with no arguments.
+    pub fn new<S: AsRef<OsStr>>(program: S) -> Self {
+        Command::new(program).into()
+    }
+
+    /// The command to run, which can be modified before the command is started with
+    /// [`spawn`](CommandInjector::spawn), for instance to add arguments or to set the working
+    /// directory. The standard input and standard output of the command are overwritten when the
+    /// command is started.
+    pub fn command(&mut self) -> &mut Command {
+        &mut self.command
+    }
+
+    /// Close the picker using the provided [`CancelHandle`] if the command cannot be started,
+    /// if its output cannot be read, or if it exits unsuccessfully. The picker then returns an
+    /// error instead of waiting for input, as with the `abort` action.
+    ///
+    /// The picker is not closed if the command is killed since the injector was disconnected or
+    /// the picker was closed. As with [`CancelHandle::cancel`], a failure while the picker is not
+    /// open has no effect, so it does not affect a later pick.
+    #[must_use]
+    pub fn abort_on_failure(mut self, handle: CancelHandle) -> Self {
+        self.abort = Some(handle);
+        self
+    }
+
+    /// Start the command, and add each line of its output to the picker using the provided
+    /// injector on a background thread.
+    ///
+    /// The returned thread finishes when the command exits and all of its output has been read,
+    /// or when the command is killed since the injector was disconnected or the picker was
+    /// closed. If the output is still open shortly after the command exits, for instance since it
+    /// is held by a process started by the command, the thread finishes without waiting for the
+    /// output, which is still added to the picker in the background.
+    ///
+    /// # Errors
+    /// The thread returns an error if the command cannot be started, if its output cannot be
+    /// read, or if it exits unsuccessfully. Otherwise, the thread returns the exit status of the
+    /// command, which is not successful if the command was killed.
+    pub fn spawn<T, R>(self, injector: Injector<T, R>) -> JoinHandle<Result<ExitStatus, io::Error>>
+    where
+        T: From<String> + Send + Sync + 'static,
+        R: Render<T> + Send + Sync + 'static,
+    {
+        let Self { mut command, abort } = self;
+        // record the number of closed picks before starting the thread, so that the command is
+        // killed even if the picker is closed before the thread starts
+        let closed = injector.picks_closed();
+        thread::spawn(move || {
+            let res = run(&mut command, &injector, closed);
+            // do not close a picker which was opened after the picker which started the command
+            if res.is_err() && injector.picks_closed() == closed {
+                if let Some(abort) = abort {
+                    abort.cancel();
+                }
+            }
+            res
+        })
+    }
+}
+
+impl From<Command> for CommandInjector {
+    fn from(command: Command) -> Self {
+        Self {
+            command,
+            abort: None,
+        }
+    }
+}
+
+/// Run the command to completion, or until the injector is disconnected or the picker is closed,
+/// where `closed` is the number of times the picker was closed when the command was started.
+fn run<T, R>(
+    command: &mut Command,
+    injector: &Injector<T, R>,
+    closed: u64,
+) -> Result<ExitStatus, io::Error>
+where
+    T: From<String> + Send + Sync + 'static,
+    R: Render<T> + Send + Sync + 'static,
+{
+    let is_stopped = || !injector.is_connected() || injector.picks_closed() != closed;
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    // SAFETY: stdout was piped above
+    let stdout = child.stdout.take().unwrap();
+    let lines = injector.clone().spawn_lines(BufReader::new(stdout));
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if is_stopped() {
+            // the command may have exited in the meantime, in which case it cannot be killed
+            let _ = child.kill();
+            let status = child.wait()?;
+            // the output is no longer needed, so the reader is not joined; it finishes once the
+            // pipe is closed
+            return Ok(status);
+        }
+
+        sleep(POLL_INTERVAL);
+    };
+
+    // the output may be held open by another process, so only wait for a short time
+    let deadline = Instant::now() + READ_TIMEOUT;
+    while !lines.is_finished() && !is_stopped() && Instant::now() < deadline {
+        sleep(POLL_INTERVAL);
+    }
+    if lines.is_finished() {
+        match lines.join() {
+            Ok(res) => res?,
+            Err(_) => return Err(io::Error::other("reader thread panicked")),
+        };
+    }
+
+    if status.success() || is_stopped() {
+        Ok(status)
+    } else {
+        Err(io::Error::other(format!("command failed: {status}")))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::{render::StrRenderer, Picker};
+
+    fn shell(script: &str) -> CommandInjector {
+        let mut command = CommandInjector::new("sh");
+        command.command().args(["-c", script]);
+        command
+    }
+
+    #[test]
+    fn test_output() {
+        let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+        let status = shell("printf 'foo\\nbar\\r\\nbaz'")
+            .spawn(picker.injector())
+            .join()
+            .unwrap()
+            .unwrap();
+        assert!(status.success());
+
+        let mut items: Vec<&String> = picker.filter("").collect();
+        items.sort();
+        assert_eq!(items, ["bar", "baz", "foo"]);
+    }
+
+    #[test]
+    fn test_failure() {
+        let picker: Picker<String, _> = Picker::new(StrRenderer);
+        let cancel = picker.cancel_handle();
+
+        assert!(shell("echo foo; exit 3")
+            .abort_on_failure(cancel)
+            .spawn(picker.injector())
+            .join()
+            .unwrap()
+            .is_err());
+        assert!(CommandInjector::new("/nonexistent/command")
+            .spawn(picker.injector())
+            .join()
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn test_kill_on_restart() {
+        let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+        let handle = shell("echo foo; exec sleep 10").spawn(picker.injector());
+
+        let start = Instant::now();
+        picker.restart();
+        let status = handle.join().unwrap().unwrap();
+        assert!(!status.success());
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(!picker.is_loading());
+    }
+
+    #[test]
+    fn test_kill_on_close() {
+        let picker: Picker<String, _> = Picker::new(StrRenderer);
+        let open = picker.cancel.open();
+        let handle = shell("exec sleep 10").spawn(picker.injector());
+
+        let start = Instant::now();
+        drop(open);
+        assert!(!handle.join().unwrap().unwrap().success());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_failure_after_close() {
+        let picker: Picker<String, _> = Picker::new(StrRenderer);
+        assert!(shell("exit 1")
+            .abort_on_failure(picker.cancel_handle())
+            .spawn(picker.injector())
+            .join()
+            .unwrap()
+            .is_err());
+
+        // the failure does not cancel the next pick
+        let _open = picker.cancel.open();
+        assert!(!picker.cancel.is_cancelled());
+    }
+
+    #[test]
+    fn test_output_held_open() {
+        let picker: Picker<String, _> = Picker::new(StrRenderer);
+
+        // the background process keeps the output open after the command exits
+        let start = Instant::now();
+        let status = shell("sleep 10 & echo foo")
+            .spawn(picker.injector())
+            .join()
+            .unwrap()
+            .unwrap();
+        assert!(status.success());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
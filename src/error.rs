@@ -0,0 +1,112 @@
+use std::{error::Error, fmt, io};
+
+/// The reason that an interactive [`Picker`](super::Picker) failed.
+///
+/// The pick methods return an [`io::Error`], so that underlying IO errors from the standard
+/// library or [`crossterm`] can be propagated. When the picker itself fails, the returned error
+/// contains a `PickError`, which can be recovered with [`PickError::from_io_error`] instead of
+/// matching on the error message.
+///
+/// ## Example
+/// ```no_run
+/// use nucleo_picker::{render::StrRenderer, PickError, Picker};
+///
+/// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+///
+/// match picker.pick() {
+///     Ok(choice) => println!("{choice:?}"),
+///     Err(err) => match PickError::from_io_error(&err) {
+///         Some(PickError::KeyboardInterrupt) => eprintln!("interrupted"),
+///         Some(err) if err.is_recoverable() => eprintln!("closed: {err}"),
+///         _ => return Err(err),
+///     },
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PickError {
+    /// The target on which the picker is drawn is not interactive.
+    NotInteractive,
+    /// The picker was closed by the [`Abort`](crate::bind::Action::Abort) action, which is bound
+    /// to `ctrl + c` by default.
+    KeyboardInterrupt,
+    /// The picker was closed by a [`CancelHandle`](crate::CancelHandle).
+    Cancelled,
+    /// The picker was closed by a termination signal, but the process was not terminated by the
+    /// signal.
+    Terminated,
+    /// Writing a frame to the terminal failed, so the screen may only be partially drawn.
+    Draw(io::Error),
+}
+
+impl PickError {
+    /// Get the `PickError` contained in an [`io::Error`] returned by the picker, or `None` if the
+    /// error was not caused by the picker itself.
+    #[must_use]
+    pub fn from_io_error(err: &io::Error) -> Option<&Self> {
+        err.get_ref().and_then(|err| err.downcast_ref())
+    }
+
+    /// Whether or not the picker can be opened again after this error. This is the case if the
+    /// picker was closed on request, rather than because the terminal could not be used.
+    #[must_use]
+    pub fn is_recoverable(&self) -> bool {
+        matches!(self, Self::KeyboardInterrupt | Self::Cancelled)
+    }
+}
+
+impl fmt::Display for PickError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotInteractive => f.write_str("is not interactive"),
+            Self::KeyboardInterrupt => f.write_str("keyboard interrupt"),
+            Self::Cancelled => f.write_str("cancelled"),
+            Self::Terminated => f.write_str("terminated by signal"),
+            Self::Draw(err) => write!(f, "failed to draw the picker: {err}"),
+        }
+    }
+}
+
+impl Error for PickError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Draw(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<PickError> for io::Error {
+    /// Convert into an [`io::Error`], which has the kind of the underlying error for
+    /// [`PickError::Draw`], and [`io::ErrorKind::Other`] otherwise.
+    fn from(err: PickError) -> Self {
+        match &err {
+            PickError::Draw(inner) => io::Error::new(inner.kind(), err),
+            _ => io::Error::other(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_io_error() {
+        let err = io::Error::from(PickError::Cancelled);
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        assert_eq!(err.to_string(), "cancelled");
+        assert!(matches!(
+            PickError::from_io_error(&err),
+            Some(PickError::Cancelled)
+        ));
+
+        // the kind of the underlying error is kept
+        let err = io::Error::from(PickError::Draw(io::ErrorKind::BrokenPipe.into()));
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+        assert!(!PickError::from_io_error(&err).unwrap().is_recoverable());
+
+        assert!(PickError::from_io_error(&io::Error::other("other")).is_none());
+    }
+}
@@ -0,0 +1,113 @@
+//! # Populate a [`Picker`](super::Picker) with the contents of a directory
+//!
+//! This module is enabled with the `fs` feature. It contains a [`DirInjector`], which walks a
+//! directory on background threads and sends the entries to a picker, as well as a renderer
+//! [`DirEntryRender`] for the entries. Directories are walked using the [`ignore`] crate, so
+//! that files which are ignored by `.gitignore` or `.ignore` files are skipped by default.
+use std::{
+    borrow::Cow,
+    path::Path,
+    thread::{self, JoinHandle},
+};
+
+pub use ignore;
+use ignore::{DirEntry, WalkBuilder, WalkState};
+
+use super::{Injector, Render};
+
+/// A renderer for a [`DirEntry`], which displays the path of the entry.
+///
+/// The path is converted to a string lossily, so that any non-Unicode sequences are replaced with
+/// the replacement character `�`.
+pub struct DirEntryRender;
+
+impl Render<DirEntry> for DirEntryRender {
+    type Str<'a> = Cow<'a, str>;
+
+    fn render<'a>(&self, entry: &'a DirEntry) -> Self::Str<'a> {
+        entry.path().to_string_lossy()
+    }
+}
+
+/// Walk a directory on background threads, and add each entry to a picker.
+///
+/// The walk is configured with an [`ignore::WalkBuilder`], which respects `.gitignore` files,
+/// skips hidden files, and uses multiple threads by default. Use
+/// [`walk_builder`](DirInjector::walk_builder) to change the configuration, for instance to
+/// include hidden files or to limit the depth.
+///
+/// Entries which cannot be read, for instance because of insufficient permissions, are skipped.
+/// The walk stops early if the injector is disconnected from the picker, for instance if the
+/// picker is restarted with [`Picker::restart`](super::Picker::restart) or dropped. In particular,
+/// the walk is cancelled by restarting the picker.
+///
+/// ## Example
+/// ```no_run
+/// use nucleo_picker::{
+///     fs::{DirEntryRender, DirInjector},
+///     nucleo::Config,
+///     PickerOptions,
+/// };
+///
+/// let mut picker = PickerOptions::new()
+///     .config(Config::DEFAULT.match_paths())
+///     .picker(DirEntryRender);
+///
+/// let mut walker = DirInjector::new(".");
+/// walker.walk_builder().hidden(false).max_depth(Some(4));
+/// walker.spawn(picker.injector());
+///
+/// if let Some(entry) = picker.pick()? {
+///     println!("{}", entry.path().display());
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct DirInjector {
+    walk_builder: WalkBuilder,
+}
+
+impl DirInjector {
+    /// Walk the provided directory with the default configuration.
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            walk_builder: WalkBuilder::new(root),
+        }
+    }
+
+    /// The configuration of the walk, which can be modified before the walk is started with
+    /// [`spawn`](DirInjector::spawn).
+    pub fn walk_builder(&mut self) -> &mut WalkBuilder {
+        &mut self.walk_builder
+    }
+
+    /// Start walking the directory on background threads, adding each entry to the picker using
+    /// the provided injector.
+    ///
+    /// The returned thread finishes when the walk is complete, or when the walk is stopped early
+    /// since the injector was disconnected. The picker is [loading](super::Picker::is_loading)
+    /// until the walk finishes.
+    pub fn spawn<R: Render<DirEntry> + Send + Sync + 'static>(
+        self,
+        injector: Injector<DirEntry, R>,
+    ) -> JoinHandle<()> {
+        let walk = self.walk_builder.build_parallel();
+        thread::spawn(move || {
+            walk.run(|| {
+                let injector = injector.clone();
+                Box::new(move |entry| match entry {
+                    Ok(entry) => match injector.try_push(entry) {
+                        Ok(()) => WalkState::Continue,
+                        Err(_) => WalkState::Quit,
+                    },
+                    Err(_) => WalkState::Continue,
+                })
+            });
+        })
+    }
+}
+
+impl From<WalkBuilder> for DirInjector {
+    fn from(walk_builder: WalkBuilder) -> Self {
+        Self { walk_builder }
+    }
+}
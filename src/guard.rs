@@ -0,0 +1,133 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, IsTerminal, StderrLock, StdoutLock, Write},
+};
+
+use crossterm::{
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+use super::{PickError, Target};
+
+/// The path of the controlling terminal.
+#[cfg(unix)]
+const TTY_PATH: &str = "/dev/tty";
+#[cfg(windows)]
+const TTY_PATH: &str = "CONOUT$";
+
+/// The locked output handle corresponding to a [`Target`].
+pub(crate) enum TargetWriter {
+    Stdout(StdoutLock<'static>),
+    Stderr(StderrLock<'static>),
+    File(File),
+}
+
+impl TargetWriter {
+    /// Open the target, returning an error if it is not interactive. If `tty_fallback` is true
+    /// and the target is not interactive, the controlling terminal is opened instead.
+    pub fn open(target: Target, tty_fallback: bool) -> Result<Self, io::Error> {
+        let (writer, is_terminal) = match target {
+            Target::Stdout => {
+                let stdout = io::stdout().lock();
+                let is_terminal = stdout.is_terminal();
+                (Self::Stdout(stdout), is_terminal)
+            }
+            Target::Stderr => {
+                let stderr = io::stderr().lock();
+                let is_terminal = stderr.is_terminal();
+                (Self::Stderr(stderr), is_terminal)
+            }
+            Target::File(file) => {
+                let is_terminal = file.is_terminal();
+                (Self::File(file), is_terminal)
+            }
+            Target::Tty => {
+                let file = OpenOptions::new().read(true).write(true).open(TTY_PATH)?;
+                let is_terminal = file.is_terminal();
+                (Self::File(file), is_terminal)
+            }
+        };
+
+        if is_terminal {
+            Ok(writer)
+        } else if tty_fallback {
+            Self::open(Target::Tty, false)
+        } else {
+            Err(PickError::NotInteractive.into())
+        }
+    }
+}
+
+impl Write for TargetWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Stdout(stdout) => stdout.write(buf),
+            Self::Stderr(stderr) => stderr.write(buf),
+            Self::File(file) => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(stdout) => stdout.flush(),
+            Self::Stderr(stderr) => stderr.flush(),
+            Self::File(file) => file.flush(),
+        }
+    }
+}
+
+/// A guard which keeps the terminal in raw mode on the alternate screen until it is dropped.
+///
+/// By default, each call to one of the pick methods of a [`Picker`](super::Picker) enters the
+/// alternate screen when the picker opens, and leaves it when the picker closes. Applications
+/// which open the picker several times in a row, or which draw their own interface around the
+/// picker, can instead create a guard and open the picker with
+/// [`Picker::pick_in`](super::Picker::pick_in). The screen is then only restored once the guard
+/// is dropped, so it does not flash between picks.
+///
+/// The guard holds a lock to the [`Target`] if it is stdout or stderr; see
+/// [`Picker::pick`](super::Picker::pick) for more detail.
+///
+/// ## Example
+/// ```no_run
+/// use nucleo_picker::{render::StrRenderer, Picker, Target, TerminalGuard};
+///
+/// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+/// picker.extend(["foo", "bar"].map(String::from));
+///
+/// let mut guard = TerminalGuard::new(Target::Stderr)?;
+/// let first = picker.pick_in(&mut guard)?.selection.map(|s| s.into_pick());
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct TerminalGuard {
+    writer: BufWriter<TargetWriter>,
+}
+
+impl TerminalGuard {
+    /// Enable raw mode and enter the alternate screen on the provided target.
+    ///
+    /// # Errors
+    /// Underlying IO errors from the standard library or [`crossterm`] will be propogated. This
+    /// fails with [`PickError::NotInteractive`] if the target is not interactive.
+    pub fn new(target: Target) -> Result<Self, io::Error> {
+        let mut writer = BufWriter::new(TargetWriter::open(target, false)?);
+        enable_raw_mode()?;
+        execute!(writer, EnterAlternateScreen)?;
+        Ok(Self { writer })
+    }
+
+    /// The buffered output handle of the guard.
+    pub(crate) fn writer(&mut self) -> &mut BufWriter<TargetWriter> {
+        &mut self.writer
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // errors cannot be propagated from a destructor, and there is nothing else we can do to
+        // restore the terminal
+        let _ = execute!(self.writer, LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
@@ -1,8 +1,90 @@
-use std::sync::Arc;
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt,
+    io::{self, BufRead},
+    mem,
+    num::NonZero,
+    sync::{Arc, PoisonError, RwLock},
+    thread::{self, JoinHandle},
+};
 
 use nucleo as nc;
 
-use super::Render;
+use super::{
+    cancel::CancelState,
+    progress::ProgressCallback,
+    term::{normalize_query_string, sanitize_rendered},
+    Progress, Render,
+};
+
+/// The separator between the rendered item and its keywords in the matcher column.
+///
+/// The separator is removed from the keywords, but it may appear in the rendered item if the
+/// rendered text is not sanitized. Therefore the rendered item ends at the last separator, and a
+/// separator is appended to a rendered item which contains the separator even if there are no
+/// keywords.
+const KEYWORD_SEPARATOR: char = '\0';
+
+/// The matcher column for a rendered item and its keywords.
+fn matcher_column(rendered: &str, keywords: Option<Cow<'_, str>>) -> nc::Utf32String {
+    match keywords {
+        Some(keywords) => {
+            let mut keywords = keywords.into_owned();
+            normalize_query_string(&mut keywords);
+            keywords.retain(|ch| ch != KEYWORD_SEPARATOR);
+            let mut text = String::with_capacity(rendered.len() + 1 + keywords.len());
+            text.push_str(rendered);
+            text.push(KEYWORD_SEPARATOR);
+            text.push_str(&keywords);
+            text.into()
+        }
+        None if rendered.contains(KEYWORD_SEPARATOR) => {
+            let mut text = String::with_capacity(rendered.len() + 1);
+            text.push_str(rendered);
+            text.push(KEYWORD_SEPARATOR);
+            text.into()
+        }
+        None => rendered.into(),
+    }
+}
+
+/// The part of the matcher column which corresponds to the rendered item, excluding any
+/// keywords.
+pub(crate) fn rendered_column<'a>(column: &'a nc::Utf32String) -> nc::Utf32Str<'a> {
+    let text = column.slice(..);
+    let len = match text {
+        nc::Utf32Str::Ascii(bytes) => memchr::memrchr(KEYWORD_SEPARATOR as u8, bytes),
+        nc::Utf32Str::Unicode(chars) => chars.iter().rposition(|ch| *ch == KEYWORD_SEPARATOR),
+    };
+    match len {
+        Some(len) => column.slice(..len),
+        None => text,
+    }
+}
+
+/// Whether or not the injectors of a matcher engine are still connected to the picker.
+///
+/// An injector holds the read lock while it checks the connection and adds an item, so that the
+/// picker cannot restart the engine in between: the picker disconnects the injectors with the
+/// write lock before the engine is restarted.
+#[derive(Debug)]
+pub(crate) struct Connection(RwLock<bool>);
+
+impl Connection {
+    pub(crate) fn new() -> Self {
+        Self(RwLock::new(true))
+    }
+
+    /// Disconnect the injectors, waiting for any in-progress [`Injector::try_push`] to finish.
+    pub(crate) fn disconnect(&self) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = false;
+    }
+
+    pub(crate) fn is_connected(&self) -> bool {
+        *self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+}
 
 /// A handle which allows adding new items to a [`Picker`](super::Picker).
 ///
@@ -16,6 +98,8 @@ use super::Render;
 /// With this feature enabled, an injector implements
 /// [`DeserializeSeed`](::serde::de::DeserializeSeed) and expects a sequence of picker items.
 /// ```
+/// # #[cfg(feature = "serde")]
+/// # fn main() {
 /// use nucleo_picker::{render::StrRenderer, Picker, Render};
 /// use serde::{de::DeserializeSeed, Deserialize};
 /// use serde_json::Deserializer;
@@ -39,10 +123,21 @@ use super::Render;
 /// injector
 ///     .deserialize(&mut Deserializer::from_str(input))
 ///     .unwrap();
+/// # }
+/// # #[cfg(not(feature = "serde"))]
+/// # fn main() {}
 /// ```
 pub struct Injector<T, R> {
     inner: nc::Injector<T>,
     render: Arc<R>,
+    connected: Arc<Connection>,
+    /// Whether or not control characters are removed from the rendered items.
+    sanitize: bool,
+    /// The number of items added by every injector of the picker.
+    progress: Progress,
+    on_progress: Option<ProgressCallback>,
+    /// Whether or not the picker is open.
+    cancel: Arc<CancelState>,
 }
 
 impl<T, R> Clone for Injector<T, R> {
@@ -50,22 +145,209 @@ impl<T, R> Clone for Injector<T, R> {
         Self {
             inner: self.inner.clone(),
             render: self.render.clone(),
+            connected: self.connected.clone(),
+            sanitize: self.sanitize,
+            progress: self.progress.clone(),
+            on_progress: self.on_progress.clone(),
+            cancel: self.cancel.clone(),
         }
     }
 }
 
 impl<T: Send + Sync + 'static, R: Render<T>> Injector<T, R> {
-    pub(crate) fn new(inner: nc::Injector<T>, render: Arc<R>) -> Self {
-        Self { inner, render }
+    pub(crate) fn new(
+        inner: nc::Injector<T>,
+        render: Arc<R>,
+        connected: Arc<Connection>,
+        sanitize: bool,
+        progress: Progress,
+        cancel: Arc<CancelState>,
+    ) -> Self {
+        Self {
+            inner,
+            render,
+            connected,
+            sanitize,
+            progress,
+            on_progress: None,
+            cancel,
+        }
     }
 }
 
 impl<T, R: Render<T>> Injector<T, R> {
     /// Add an item to the picker.
+    ///
+    /// If the picker was restarted after this injector was created, the item is silently
+    /// dropped. Use [`try_push`](Injector::try_push) to detect this case.
     pub fn push(&self, item: T) {
+        let mut bytes = 0;
         self.inner.push(item, |s, columns| {
-            columns[0] = self.render.render(s).as_ref().into();
+            let rendered = self.render.render(s);
+            let rendered = if self.sanitize {
+                sanitize_rendered(rendered.as_ref())
+            } else {
+                Cow::Borrowed(rendered.as_ref())
+            };
+            bytes = rendered.len();
+
+            columns[0] = matcher_column(&rendered, self.render.keywords(s));
         });
+
+        let items = self.progress.record(bytes);
+        if let Some(on_progress) = &self.on_progress {
+            on_progress.notify(items, &self.progress);
+        }
+    }
+
+    /// Add an item to the picker, or return the item if the injector is no longer connected to
+    /// the picker.
+    ///
+    /// An injector is disconnected when the picker is restarted, for instance with
+    /// [`Picker::restart`](super::Picker::restart), or when the picker is dropped. This is useful
+    /// to notice that a producer thread should stop, or should obtain a new injector.
+    ///
+    /// # Errors
+    /// Returns a [`PushError`] containing the item if the injector is disconnected, in which case
+    /// the item is not added to the picker. The connection is checked atomically with respect to
+    /// restarting the picker, so an item for which `Ok` is returned was added before the restart.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker};
+    ///
+    /// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+    /// let injector = picker.injector();
+    /// assert!(injector.try_push("before".to_owned()).is_ok());
+    ///
+    /// picker.restart();
+    /// let err = injector.try_push("after".to_owned()).unwrap_err();
+    /// assert_eq!(err.into_inner(), "after");
+    /// ```
+    pub fn try_push(&self, item: T) -> Result<(), PushError<T>> {
+        let connected = self
+            .connected
+            .0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        if *connected {
+            self.push(item);
+            Ok(())
+        } else {
+            Err(PushError(item))
+        }
+    }
+
+    /// Whether or not the injector is still connected to the picker.
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        self.connected.is_connected()
+    }
+
+    /// The number of times the interactive picker was closed.
+    pub(crate) fn picks_closed(&self) -> u64 {
+        self.cancel.closed_count()
+    }
+
+    /// Get a [`Progress`] handle which counts the items added to the picker by this injector and
+    /// by every other injector of the picker.
+    #[must_use]
+    pub fn progress(&self) -> Progress {
+        self.progress.clone()
+    }
+
+    /// Call the provided closure each time the total number of items added to the picker is a
+    /// multiple of `interval`. The closure is called on the thread which added the item, so it
+    /// should return quickly.
+    ///
+    /// The closure is only called for items which are added by this injector, or by injectors
+    /// which are cloned from it after this method is called.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::num::NonZero;
+    ///
+    /// use nucleo_picker::{render::StrRenderer, Picker};
+    ///
+    /// let picker: Picker<String, _> = Picker::new(StrRenderer);
+    /// let injector = picker
+    ///     .injector()
+    ///     .on_progress(NonZero::new(1000).unwrap(), |progress| {
+    ///         eprintln!("indexed {} items", progress.items());
+    ///     });
+    ///
+    /// for i in 0..10_000 {
+    ///     injector.push(i.to_string());
+    /// }
+    /// ```
+    #[must_use]
+    pub fn on_progress<F: Fn(&Progress) + Send + Sync + 'static>(
+        mut self,
+        interval: NonZero<u64>,
+        callback: F,
+    ) -> Self {
+        self.on_progress = Some(ProgressCallback::new(interval, callback));
+        self
+    }
+}
+
+impl<T, R> Injector<T, R>
+where
+    T: From<String> + Send + Sync + 'static,
+    R: Render<T> + Send + Sync + 'static,
+{
+    /// Spawn a thread which reads lines from `reader` and adds each line to the picker, until the
+    /// end of the input is reached or the injector is disconnected from the picker.
+    ///
+    /// Trailing newlines (`\n` or `\r\n`) are removed from each line, and invalid UTF-8 is replaced
+    /// with the replacement character `�`. The thread returns the number of lines which were added
+    /// to the picker.
+    ///
+    /// ## Example
+    /// Read items from stdin, as in the [`fzf`
+    /// example](https://github.com/autobib/nucleo-picker/tree/master/examples/fzf.rs).
+    /// ```
+    /// use std::io::Cursor;
+    ///
+    /// use nucleo_picker::{render::StrRenderer, Picker};
+    ///
+    /// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+    /// let handle = picker.injector().spawn_lines(Cursor::new("one\ntwo\r\nthree"));
+    ///
+    /// // the picker is loading until the thread exits
+    /// assert_eq!(handle.join().unwrap()?, 3);
+    /// assert!(!picker.is_loading());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// The thread stops at the first IO error returned by the reader, and returns the error.
+    pub fn spawn_lines<B: BufRead + Send + 'static>(
+        self,
+        mut reader: B,
+    ) -> JoinHandle<Result<usize, io::Error>> {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            let mut count = 0;
+            while reader.read_until(b'\n', &mut buf)? != 0 {
+                if buf.last() == Some(&b'\n') {
+                    buf.pop();
+                    if buf.last() == Some(&b'\r') {
+                        buf.pop();
+                    }
+                }
+
+                let line = match String::from_utf8(mem::take(&mut buf)) {
+                    Ok(line) => line,
+                    Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
+                };
+                if self.try_push(line.into()).is_err() {
+                    break;
+                }
+                count += 1;
+            }
+            Ok(count)
+        })
     }
 }
 
@@ -77,6 +359,32 @@ impl<T, R: Render<T>> Extend<T> for Injector<T, R> {
     }
 }
 
+/// An error returned by [`Injector::try_push`] when the injector is no longer connected to the
+/// picker. The error contains the item which could not be added.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct PushError<T>(pub T);
+
+impl<T> PushError<T> {
+    /// Recover the item which could not be added to the picker.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for PushError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PushError { .. }")
+    }
+}
+
+impl<T> fmt::Display for PushError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("injector is disconnected from the picker")
+    }
+}
+
+impl<T> Error for PushError<T> {}
+
 #[cfg(feature = "serde")]
 mod serde {
     use serde::{
@@ -127,3 +435,25 @@ mod serde {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered(text: &str, keywords: Option<&str>) -> String {
+        let column = matcher_column(text, keywords.map(Cow::Borrowed));
+        rendered_column(&column).chars().collect()
+    }
+
+    #[test]
+    fn test_rendered_column() {
+        assert_eq!(rendered("foo", None), "foo");
+        assert_eq!(rendered("foo", Some("bar")), "foo");
+        assert_eq!(rendered("föo", Some("bär")), "föo");
+
+        // the separator may appear in the rendered item, but not in the keywords
+        assert_eq!(rendered("foo\0bar", None), "foo\0bar");
+        assert_eq!(rendered("foo\0bar", Some("baz")), "foo\0bar");
+        assert_eq!(rendered("föo\0bar\0", Some("b\0az")), "föo\0bar\0");
+    }
+}
@@ -21,18 +21,40 @@
 //! ```no_run
 #![doc = include_str!("../examples/find.rs")]
 //! ```
+//!
+//! ## Optional features
+//! - `fs`: enable the `fs` module, which walks a directory on background threads to populate
+//!   the picker, respecting `.gitignore` files.
+//! - `serde`: implement `Deserialize` for [`PickerOptions`] and for the
+//!   [key bindings](bind::KeyBindings).
+//! - `tracing`: emit [`tracing`](https://docs.rs/tracing) spans and events from the interactive
+//!   picker, such as the events processed, the matcher status after each tick, the recomputed
+//!   layout, and the time taken by each frame. Install a subscriber which writes to a file, since
+//!   the terminal is in use by the picker.
 
 #![deny(missing_docs)]
 #![warn(rustdoc::unescaped_backticks)]
 
-mod bind;
+pub mod bind;
+mod cancel;
+pub mod command;
+mod error;
+#[cfg(feature = "fs")]
+pub mod fs;
+mod guard;
 mod injector;
+mod pick;
+mod progress;
 pub mod render;
+mod signal;
+mod state;
+mod status;
 mod term;
 
 use std::{
     borrow::Cow,
-    io::{self, BufWriter, IsTerminal, Write},
+    fs::File,
+    io::{self, BufWriter, Write},
     iter::Extend,
     num::NonZero,
     sync::Arc,
@@ -41,10 +63,12 @@ use std::{
 };
 
 use crossterm::{
-    event::{DisableBracketedPaste, EnableBracketedPaste},
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
     execute,
+    style::ContentStyle,
     terminal::{
-        disable_raw_mode, enable_raw_mode, size, EnterAlternateScreen, LeaveAlternateScreen,
+        disable_raw_mode, enable_raw_mode, size, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen,
     },
 };
 use nucleo::{
@@ -53,12 +77,30 @@ use nucleo::{
     Nucleo,
 };
 
+pub use crossterm;
 pub use nucleo;
 
-pub use crate::injector::Injector;
+pub use crate::cancel::CancelHandle;
+pub use crate::error::PickError;
+pub use crate::guard::TerminalGuard;
+pub use crate::injector::{Injector, PushError};
+pub use crate::pick::{Outcome, Pick, Selection};
+pub use crate::progress::Progress;
+pub use crate::state::{PickerState, StateSnapshot};
+pub use crate::status::{FrameInfo, Status};
+pub use crate::term::{ColorMode, Scrollbar, Theme, Truncate, WidthPolicy};
 use crate::{
+    bind::KeyBindings,
+    cancel::CancelState,
+    guard::TargetWriter,
+    injector::Connection,
+    signal::SignalHandlers,
+    state::SharedState,
+    status::{FrameHook, StatusLine},
     term::normalize_query_string,
-    term::{Compositor, CompositorBuffer, EventSummary, PickerConfig},
+    term::{
+        is_legacy_console, reserve_rows, Compositor, CompositorBuffer, EventSummary, PickerConfig,
+    },
 };
 
 /// A trait which describes how to render objects for matching and display.
@@ -129,7 +171,8 @@ use crate::{
 /// screen.
 ///
 /// It is best to avoid such characters in your rendered format. If you do not have control
-/// over the incoming data, the most robust solution is likely to perform substitutions during
+/// over the incoming data, the simplest solution is to enable
+/// [`PickerOptions::sanitize_rendered`]. Otherwise, you can perform substitutions during
 /// rendering.
 /// ```
 /// # use nucleo_picker::Render;
@@ -203,6 +246,182 @@ pub trait Render<T> {
     /// Render the given item as it should appear in the picker. See the
     /// [trait-level docs](Render) for more detail.
     fn render<'a>(&self, item: &'a T) -> Self::Str<'a>;
+
+    /// Additional text, such as keywords or aliases, which is matched by the query but which is
+    /// not displayed in the picker. By default, there are no keywords.
+    ///
+    /// The keywords are matched as if they were appended to the rendered item. Control
+    /// characters are removed from the keywords, and newlines and tabs are converted to spaces.
+    ///
+    /// ## Example
+    /// An emoji picker which matches the aliases of each emoji, but only displays the emoji and
+    /// its name.
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use nucleo_picker::{Picker, Render};
+    ///
+    /// struct Emoji {
+    ///     symbol: &'static str,
+    ///     name: &'static str,
+    ///     aliases: &'static str,
+    /// }
+    ///
+    /// struct EmojiRenderer;
+    ///
+    /// impl Render<Emoji> for EmojiRenderer {
+    ///     type Str<'a> = String;
+    ///
+    ///     fn render<'a>(&self, item: &'a Emoji) -> Self::Str<'a> {
+    ///         format!("{} {}", item.symbol, item.name)
+    ///     }
+    ///
+    ///     fn keywords<'a>(&self, item: &'a Emoji) -> Option<Cow<'a, str>> {
+    ///         Some(Cow::Borrowed(item.aliases))
+    ///     }
+    /// }
+    ///
+    /// let mut picker = Picker::new(EmojiRenderer);
+    /// picker.extend([
+    ///     Emoji { symbol: "😄", name: "grinning face", aliases: ":smile:" },
+    ///     Emoji { symbol: "👍", name: "thumbs up", aliases: ":+1: :thumbsup:" },
+    /// ]);
+    ///
+    /// let matches: Vec<&str> = picker.filter("smile").map(|emoji| emoji.name).collect();
+    /// assert_eq!(matches, ["grinning face"]);
+    /// ```
+    #[inline]
+    fn keywords<'a>(&self, item: &'a T) -> Option<Cow<'a, str>> {
+        let _ = item;
+        None
+    }
+
+    /// The style of the given item, such as its color or whether it is dimmed or bold. By
+    /// default, items are unstyled.
+    ///
+    /// The item style is applied on top of the [`Theme::selection`] style for the selected item,
+    /// and the [`Theme::highlight`] style is applied on top of the item style for the characters
+    /// matched by the query.
+    ///
+    /// ## Example
+    /// Display directories in blue, and files which cannot be read dimmed.
+    /// ```
+    /// use nucleo_picker::{
+    ///     crossterm::style::{Attribute, ContentStyle, Stylize},
+    ///     Render,
+    /// };
+    ///
+    /// struct Entry {
+    ///     path: String,
+    ///     is_dir: bool,
+    ///     readable: bool,
+    /// }
+    ///
+    /// struct EntryRenderer;
+    ///
+    /// impl Render<Entry> for EntryRenderer {
+    ///     type Str<'a> = &'a str;
+    ///
+    ///     fn render<'a>(&self, item: &'a Entry) -> Self::Str<'a> {
+    ///         &item.path
+    ///     }
+    ///
+    ///     fn style(&self, item: &Entry) -> ContentStyle {
+    ///         if item.is_dir {
+    ///             ContentStyle::new().blue()
+    ///         } else if !item.readable {
+    ///             ContentStyle::new().attribute(Attribute::Dim)
+    ///         } else {
+    ///             ContentStyle::new()
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn style(&self, item: &T) -> ContentStyle {
+        let _ = item;
+        ContentStyle::new()
+    }
+
+    /// Additional text, such as a file size or a date, which is displayed at the right edge of the
+    /// first line of the item but which is not matched by the query. By default, there is no
+    /// trailer.
+    ///
+    /// The trailer is truncated to at most half of the width of the screen, and the rendered item
+    /// is truncated so that it does not overlap the trailer. The style of the trailer is set by
+    /// [`Theme::trailer`]. Control characters are removed from the trailer, and newlines and tabs
+    /// are converted to spaces.
+    ///
+    /// ## Example
+    /// Display the size of each file next to its path.
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use nucleo_picker::Render;
+    ///
+    /// struct File {
+    ///     path: String,
+    ///     size: u64,
+    /// }
+    ///
+    /// struct FileRenderer;
+    ///
+    /// impl Render<File> for FileRenderer {
+    ///     type Str<'a> = &'a str;
+    ///
+    ///     fn render<'a>(&self, item: &'a File) -> Self::Str<'a> {
+    ///         &item.path
+    ///     }
+    ///
+    ///     fn trailer<'a>(&self, item: &'a File) -> Option<Cow<'a, str>> {
+    ///         Some(Cow::Owned(format!("{} B", item.size)))
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn trailer<'a>(&self, item: &'a T) -> Option<Cow<'a, str>> {
+        let _ = item;
+        None
+    }
+
+    /// An icon, such as a file type glyph from a patched font, which is displayed at the left edge
+    /// of the first line of the item but which is not matched by the query. By default, there is
+    /// no icon.
+    ///
+    /// Icons are only displayed if enabled with [`PickerOptions::icons`], which reserves two
+    /// columns for the icon before the selection marker. Icons which are not one or two columns
+    /// wide, such as control characters, are not displayed.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{Picker, PickerOptions, Render};
+    ///
+    /// struct Entry {
+    ///     path: String,
+    ///     is_dir: bool,
+    /// }
+    ///
+    /// struct EntryRenderer;
+    ///
+    /// impl Render<Entry> for EntryRenderer {
+    ///     type Str<'a> = &'a str;
+    ///
+    ///     fn render<'a>(&self, item: &'a Entry) -> Self::Str<'a> {
+    ///         &item.path
+    ///     }
+    ///
+    ///     fn icon(&self, item: &Entry) -> Option<char> {
+    ///         Some(if item.is_dir { '📁' } else { '📄' })
+    ///     }
+    /// }
+    ///
+    /// let picker: Picker<Entry, _> = PickerOptions::new().icons(true).picker(EntryRenderer);
+    /// ```
+    #[inline]
+    fn icon(&self, item: &T) -> Option<char> {
+        let _ = item;
+        None
+    }
 }
 
 impl<T, R: for<'a> Fn(&'a T) -> Cow<'a, str>> Render<T> for R {
@@ -216,6 +435,29 @@ impl<T, R: for<'a> Fn(&'a T) -> Cow<'a, str>> Render<T> for R {
     }
 }
 
+/// Initialize the matcher engine with the given number of threads.
+fn new_matcher<T: Send + Sync + 'static>(
+    config: nc::Config,
+    threads: Option<NonZero<usize>>,
+) -> Nucleo<T> {
+    Nucleo::new(
+        config,
+        Arc::new(|| {}),
+        // nucleo's API is a bit weird here in that it does not accept `NonZero<usize>`
+        threads
+            .or_else(|| {
+                // Reserve two threads:
+                // 1. for populating the macher
+                // 2. for rendering the terminal UI and handling user input
+                available_parallelism()
+                    .ok()
+                    .and_then(|it| it.get().checked_sub(2).and_then(NonZero::new))
+            })
+            .map(NonZero::get),
+        1,
+    )
+}
+
 /// Specify configuration options for a [`Picker`].
 ///
 /// Initialize with [`new`](PickerOptions::new) or (equivalently) the
@@ -231,6 +473,47 @@ impl<T, R: for<'a> Fn(&'a T) -> Cow<'a, str>> Render<T> for R {
 ///     .query("search")
 ///     .picker(StrRenderer);
 /// ```
+///
+/// ### `Deserialize` implementation
+/// With the `serde` feature enabled, options can be deserialized from a map, so that they can be
+/// loaded from a configuration file. The keys are the names of the methods in kebab-case, and the
+/// options which are not present are left at their default values. The supported keys are:
+///
+/// - `highlight`, `overflow-indicators`, `restore-on-signal`, `tty-fallback`, `clear-on-exit`,
+///   `mouse`, `wrap`, `exit-if-empty`, `print-on-exit`, `loading-indicator`, `sticky-selection`,
+///   `cycle`, `icons`, and `sanitize-rendered`, which are booleans;
+/// - `highlight-padding`, `scroll-padding`, `prompt-padding`, `inline`, and `max-item-height`,
+///   which are non-negative integers;
+/// - `min-size`, which is a pair of non-negative integers `[width, height]`;
+/// - `threads`, which is a positive integer;
+/// - `timeout`, which is a number of seconds;
+/// - `query`, `prompt-prefix`, `placeholder`, and `no-match-text`, which are strings;
+/// - `case-matching`, which is one of `"smart"`, `"ignore"`, or `"respect"`;
+/// - `normalization`, which is one of `"smart"` or `"never"`;
+/// - `width-policy`, which is one of `"standard"`, `"ambiguous-wide"`, or `"codepoint"`;
+/// - `truncate`, which is one of `"end"` or `"start"`;
+/// - `color-mode`, which is one of `"auto"` or `"never"`;
+/// - `keybindings`, which is deserialized as a [`KeyBindings`] table.
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # fn main() {
+/// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+///
+/// let options: PickerOptions = serde_json::from_str(
+///     r#"{
+///         "highlight-padding": 5,
+///         "case-matching": "ignore",
+///         "prompt-prefix": "$ ",
+///         "keybindings": { "ctrl-k": "move-down", "ctrl-j": "move-up" }
+///     }"#,
+/// )
+/// .unwrap();
+/// let picker: Picker<String, _> = options.picker(StrRenderer);
+/// # }
+/// # #[cfg(not(feature = "serde"))]
+/// # fn main() {}
+/// ```
 pub struct PickerOptions {
     config: nc::Config,
     query: String,
@@ -259,23 +542,16 @@ impl PickerOptions {
 
     /// Convert into a [`Picker`].
     #[must_use]
-    pub fn picker<T: Send + Sync + 'static, R>(self, render: R) -> Picker<T, R> {
-        let matcher = Nucleo::new(
-            self.config.clone(),
-            Arc::new(|| {}),
-            // nucleo's API is a bit weird here in that it does not accept `NonZero<usize>`
-            self.threads
-                .or_else(|| {
-                    // Reserve two threads:
-                    // 1. for populating the macher
-                    // 2. for rendering the terminal UI and handling user input
-                    available_parallelism()
-                        .ok()
-                        .and_then(|it| it.get().checked_sub(2).and_then(NonZero::new))
-                })
-                .map(NonZero::get),
-            1,
-        );
+    pub fn picker<T: Send + Sync + 'static, R>(mut self, render: R) -> Picker<T, R> {
+        if !self.picker_config.color_mode.use_color() {
+            self.picker_config.theme = self.picker_config.theme.without_color();
+        }
+        if is_legacy_console() {
+            self.picker_config.legacy_console = true;
+            self.picker_config.theme = self.picker_config.theme.without_attributes();
+        }
+
+        let matcher = new_matcher(self.config.clone(), self.threads);
 
         Picker {
             matcher,
@@ -283,6 +559,13 @@ impl PickerOptions {
             picker_config: self.picker_config,
             config: self.config,
             query: self.query,
+            connected: Arc::new(Connection::new()),
+            progress: Progress::default(),
+            generation: 0,
+            cancel: Arc::new(CancelState::default()),
+            state: Arc::new(SharedState::default()),
+            reload: None,
+            query_source: None,
         }
     }
 
@@ -297,6 +580,33 @@ impl PickerOptions {
         self
     }
 
+    /// How long to wait in each frame for the matcher engine to finish processing the items
+    /// (default: 10ms).
+    ///
+    /// The matcher engine runs in the background, and the picker waits up to this duration in
+    /// each frame for the engine to finish before drawing the matches which are available. A
+    /// longer timeout means that the screen is more likely to show the final matches after each
+    /// keystroke, at the cost of slower response to input when there are many items. The frame
+    /// interval is 16ms, so timeouts longer than that reduce the frame rate.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// // prefer responsiveness over complete matches for a very large number of items
+    /// let picker: Picker<String, _> = PickerOptions::new()
+    ///     .tick_timeout(Duration::from_millis(2))
+    ///     .picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn tick_timeout(mut self, tick_timeout: Duration) -> Self {
+        self.picker_config.tick_timeout = tick_timeout;
+        self
+    }
+
     /// Set the internal matcher configuration.
     #[must_use]
     #[inline]
@@ -337,6 +647,544 @@ impl PickerOptions {
         self
     }
 
+    /// Whether or not to indicate the number of matches which are hidden above and below the
+    /// screen.
+    ///
+    /// If enabled, the top row of the screen is reserved to display the number of matches
+    /// above the screen, and the number of matches below the screen is displayed next to the
+    /// match counts.
+    #[must_use]
+    #[inline]
+    pub fn overflow_indicators(mut self, overflow_indicators: bool) -> Self {
+        self.picker_config.overflow_indicators = overflow_indicators;
+        self
+    }
+
+    /// Whether or not to restore the terminal if the process receives a termination signal while
+    /// the picker is interactive.
+    ///
+    /// If enabled, handlers for `SIGTERM`, `SIGHUP`, `SIGINT`, and `SIGQUIT` are installed while the
+    /// picker is interactive. When one of these signals is received, the terminal is restored
+    /// and then the process is terminated as if the default handler for the signal was invoked.
+    /// Any other handlers for these signals are not run.
+    ///
+    /// This option has no effect on non-Unix platforms.
+    #[must_use]
+    #[inline]
+    pub fn restore_on_signal(mut self, restore_on_signal: bool) -> Self {
+        self.picker_config.restore_on_signal = restore_on_signal;
+        self
+    }
+
+    /// Whether or not to render the picker on the controlling terminal if the requested
+    /// [`Target`] is not interactive (default: `false`).
+    ///
+    /// If enabled, the picker is rendered on [`Target::Tty`] instead of failing with the `"is not
+    /// interactive"` error, for instance if the picker is run in the middle of a shell pipeline
+    /// such as `find . | my-picker | xargs ls` with stderr redirected. Keyboard events are always
+    /// read from the controlling terminal if stdin is not interactive.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new()
+    ///     .tty_fallback(true)
+    ///     .picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn tty_fallback(mut self, tty_fallback: bool) -> Self {
+        self.picker_config.tty_fallback = tty_fallback;
+        self
+    }
+
+    /// Whether or not to clear the picker screen on exit.
+    ///
+    /// By default, the picker is rendered in an alternate screen which is left when the picker
+    /// exits, restoring the original contents of the terminal. If set to `false`, the picker
+    /// remains on the alternate screen after exiting. This is useful if the picker is immediately
+    /// re-opened, for instance by a shell binding which runs your application in a loop, since
+    /// the screen does not flash to its original state in between.
+    ///
+    /// Note that the terminal is not returned to the original screen: this must be done by the
+    /// caller, for instance by opening the picker one final time with this option set to `true`,
+    /// or by executing [`LeaveAlternateScreen`]. The original screen is still restored if the
+    /// picker is closed by a termination signal, since the process is then terminated.
+    #[must_use]
+    #[inline]
+    pub fn clear_on_exit(mut self, clear_on_exit: bool) -> Self {
+        self.picker_config.clear_on_exit = clear_on_exit;
+        self
+    }
+
+    /// Set the key bindings used by the picker. See [`KeyBindings`] for more detail.
+    #[must_use]
+    #[inline]
+    pub fn keybindings(mut self, keybindings: KeyBindings) -> Self {
+        self.picker_config.keybindings = keybindings;
+        self
+    }
+
+    /// Set the styles used to draw the picker. See [`Theme`] for more detail.
+    #[must_use]
+    #[inline]
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.picker_config.theme = theme;
+        self
+    }
+
+    /// Whether or not to draw the picker with colors (default: [`ColorMode::Auto`]). See
+    /// [`ColorMode`] for more detail.
+    #[must_use]
+    #[inline]
+    pub fn color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.picker_config.color_mode = color_mode;
+        self
+    }
+
+    /// Set the prefix drawn before the prompt. The default prefix is `"> "`.
+    ///
+    /// Control characters are removed from the prefix, and newlines and tabs are converted to
+    /// spaces.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new()
+    ///     .prompt_prefix("Search: ")
+    ///     .placeholder("type to filter")
+    ///     .picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn prompt_prefix<Q: Into<String>>(mut self, prefix: Q) -> Self {
+        self.picker_config.prompt_prefix = prefix.into();
+        normalize_query_string(&mut self.picker_config.prompt_prefix);
+        self
+    }
+
+    /// Set the placeholder text which is displayed when the prompt is empty. The style of the
+    /// placeholder is set by [`Theme::placeholder`].
+    ///
+    /// Control characters are removed from the placeholder, and newlines and tabs are converted
+    /// to spaces.
+    #[must_use]
+    #[inline]
+    pub fn placeholder<Q: Into<String>>(mut self, placeholder: Q) -> Self {
+        let mut placeholder = placeholder.into();
+        normalize_query_string(&mut placeholder);
+        self.picker_config.placeholder = Some(placeholder);
+        self
+    }
+
+    /// Set the text which is displayed in the center of the match list when there are no matches.
+    /// The text is not displayed before any items are added, or while the matcher is still
+    /// processing the items. The style of the text is set by [`Theme::placeholder`].
+    ///
+    /// Control characters are removed from the text, and newlines and tabs are converted to
+    /// spaces.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new()
+    ///     .no_match_text("No results")
+    ///     .picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn no_match_text<Q: Into<String>>(mut self, text: Q) -> Self {
+        let mut text = text.into();
+        normalize_query_string(&mut text);
+        self.picker_config.no_match_text = Some(text);
+        self
+    }
+
+    /// Whether or not to remove control characters from the rendered items (default: `false`).
+    /// If enabled, control characters other than newlines are removed from the text returned by
+    /// [`Render::render`], and TABs are converted to spaces. See the [`Render`] documentation
+    /// for more detail on why control characters are problematic.
+    ///
+    /// This must be set before any items are added to the picker.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let mut picker: Picker<String, _> = PickerOptions::new()
+    ///     .sanitize_rendered(true)
+    ///     .picker(StrRenderer);
+    /// picker.extend(["\u{1b}[31mred\u{1b}[0m\tapple".to_owned()]);
+    ///
+    /// let matches: Vec<&String> = picker.filter("redapple").collect();
+    /// assert_eq!(matches.len(), 1);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn sanitize_rendered(mut self, sanitize_rendered: bool) -> Self {
+        self.picker_config.sanitize_rendered = sanitize_rendered;
+        self
+    }
+
+    /// The maximum number of rows which can be occupied by a single item (default: `None`). If
+    /// `None`, items with many lines can fill the entire screen.
+    ///
+    /// Items with more rows are truncated, and an ellipsis is displayed next to the last row to
+    /// indicate that the item was truncated.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::num::NonZero;
+    ///
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new()
+    ///     .max_item_height(NonZero::new(3))
+    ///     .picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn max_item_height(mut self, max_item_height: Option<NonZero<u16>>) -> Self {
+        self.picker_config.max_item_height = max_item_height;
+        self
+    }
+
+    /// Whether or not to reserve two columns at the left edge of the match list for the icons
+    /// given by [`Render::icon`] (default: `false`).
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new().icons(true).picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn icons(mut self, icons: bool) -> Self {
+        self.picker_config.icons = icons;
+        self
+    }
+
+    /// Whether or not to display an animated indicator next to the match counter while items are
+    /// being loaded or matched (default: `false`). Items are being loaded until every
+    /// [`Injector`] for the picker has been dropped; see [`Picker::is_loading`].
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new()
+    ///     .loading_indicator(true)
+    ///     .picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn loading_indicator(mut self, loading_indicator: bool) -> Self {
+        self.picker_config.loading_indicator = loading_indicator;
+        self
+    }
+
+    /// Whether or not the selection should follow the selected item when the matches change
+    /// (default: `false`).
+    ///
+    /// By default, the selection remains at the same position in the list of matches, so that
+    /// changing the query or adding new items may move the selection to a different item. If
+    /// this is enabled, the selection moves to the new position of the selected item, as long as
+    /// it still matches the query, similar to `fzf --track`.
+    ///
+    /// To keep the picker responsive with many matches, the item is only searched for near its
+    /// previous position and among the first 10,000 matches. If the item is not found, the
+    /// selection remains at the same position.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new()
+    ///     .sticky_selection(true)
+    ///     .picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn sticky_selection(mut self, sticky_selection: bool) -> Self {
+        self.picker_config.sticky_selection = sticky_selection;
+        self
+    }
+
+    /// Whether or not moving the selection past the last match wraps around to the first match,
+    /// and vice versa (default: `false`). This is similar to `fzf --cycle`.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new().cycle(true).picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn cycle(mut self, cycle: bool) -> Self {
+        self.picker_config.cycle = cycle;
+        self
+    }
+
+    /// Close the picker without selecting an item if there is no keyboard or mouse input for the
+    /// given duration (default: `None`). If `None`, the picker never closes on its own.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new()
+    ///     .timeout(Some(Duration::from_secs(60)))
+    ///     .picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.picker_config.timeout = timeout;
+        self
+    }
+
+    /// Replace the match counter, e.g. `9/43`, with custom text generated from the current
+    /// [`Status`] of the picker. The text is regenerated whenever the picker is redrawn, so the
+    /// closure should be cheap to call.
+    ///
+    /// The text is displayed on a single line with the style [`Theme::counter`]. Control
+    /// characters are removed from the text, and newlines and tabs are converted to spaces.
+    /// Return an empty string to hide the match counter.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new()
+    ///     .status_line(|status| match status.selection {
+    ///         Some(index) => format!("{} of {} matches", index + 1, status.matched),
+    ///         None => format!("no matches ({} items)", status.total),
+    ///     })
+    ///     .picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn status_line<F: Fn(&Status) -> String + Send + Sync + 'static>(
+        mut self,
+        status_line: F,
+    ) -> Self {
+        self.picker_config.status_line = Some(StatusLine::new(status_line));
+        self
+    }
+
+    /// Call the provided closure once per frame with the current [`FrameInfo`], which contains
+    /// the match counts, the selection, and the time taken by the frame. This can be used to
+    /// perform side effects such as logging or collecting metrics without writing a custom event
+    /// loop.
+    ///
+    /// The closure is called from the thread running the picker after the screen is drawn, so it
+    /// should return quickly to keep the picker responsive. Frames in which nothing changed are
+    /// also reported, with [`FrameInfo::redrawn`] set to `false`.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let mut frames = 0;
+    /// let picker: Picker<String, _> = PickerOptions::new()
+    ///     .on_frame(move |info| {
+    ///         if info.redrawn {
+    ///             frames += 1;
+    ///         }
+    ///     })
+    ///     .picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn on_frame<F: FnMut(FrameInfo) + Send + 'static>(mut self, on_frame: F) -> Self {
+        self.picker_config.on_frame = Some(FrameHook::new(on_frame));
+        self
+    }
+
+    /// Draw a scrollbar on the right edge of the match list with the given glyphs, or disable the
+    /// scrollbar with `None`. The scrollbar is disabled by default. See [`Scrollbar`] for more
+    /// detail.
+    #[must_use]
+    #[inline]
+    pub fn scrollbar(mut self, scrollbar: Option<Scrollbar>) -> Self {
+        self.picker_config.scrollbar = scrollbar;
+        self
+    }
+
+    /// Whether or not to capture mouse events.
+    ///
+    /// If enabled, the scroll wheel moves the selection, horizontal scrolling scrolls the
+    /// matches left or right, clicking on a match moves the selection to the match, and double
+    /// clicking on a match selects it. Note that capturing mouse events prevents selecting text
+    /// in the terminal with the mouse.
+    #[must_use]
+    #[inline]
+    pub fn mouse(mut self, mouse: bool) -> Self {
+        self.picker_config.mouse = mouse;
+        self
+    }
+
+    /// Render the picker inline in the given number of rows below the cursor, instead of on an
+    /// alternate screen.
+    ///
+    /// If there is not enough space below the cursor, the terminal is scrolled to make space. The
+    /// height is limited by the height of the terminal, and must be at least 3 so that there is
+    /// space to draw matches; smaller values are rounded up. When the picker exits, the rows used
+    /// by the picker are erased, and the contents of the terminal above the picker are left
+    /// intact. If [`clear_on_exit`](PickerOptions::clear_on_exit) is `false`, the rows are not
+    /// erased and the cursor is placed on the line after the picker.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// // render the picker in the bottom 10 rows of the terminal
+    /// let picker: Picker<String, _> = PickerOptions::new().inline(10).picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn inline(mut self, height: u16) -> Self {
+        self.picker_config.inline_height = Some(height.max(3));
+        self
+    }
+
+    /// Set the minimum size of the picker, in columns and rows (default: no minimum). If the
+    /// terminal is smaller than the minimum size in either dimension, or if the
+    /// [inline](PickerOptions::inline) picker has fewer rows, the picker is replaced by a message
+    /// such as `terminal too small (need 40x10)`. Key presses are still processed, and the
+    /// picker is drawn again as soon as the terminal is large enough.
+    ///
+    /// Without a minimum size, the match list and the match counts are not drawn if there is not
+    /// enough space.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new().min_size(20, 5).picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn min_size(mut self, width: u16, height: u16) -> Self {
+        self.picker_config.min_size = Some((width, height));
+        self
+    }
+
+    /// Whether or not to print the selection to stdout when the picker closes (default:
+    /// `false`), which is convenient for writing shell helpers in the style of `fzf`.
+    ///
+    /// If enabled, a picked item is printed as it is rendered by [`Render::render`], and the
+    /// contents of the prompt are printed if they are accepted instead of an item. The selection
+    /// is followed by the [delimiter](PickerOptions::print_delimiter). Nothing is printed if the
+    /// picker is closed without a selection, or if the picker is opened with
+    /// [`Picker::pick_in`], since the terminal is not yet restored.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let mut picker: Picker<String, _> = PickerOptions::new()
+    ///     .print_on_exit(true)
+    ///     .picker(StrRenderer);
+    /// picker.extend(["foo", "bar"].map(String::from));
+    ///
+    /// if picker.pick()?.is_none() {
+    ///     std::process::exit(1);
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn print_on_exit(mut self, print_on_exit: bool) -> Self {
+        self.picker_config.print_on_exit = print_on_exit;
+        self
+    }
+
+    /// The character printed after the selection if
+    /// [`print_on_exit`](PickerOptions::print_on_exit) is enabled (default: `'\n'`). Use `'\0'`
+    /// to separate the output with null characters, similar to `fzf --print0`.
+    #[must_use]
+    #[inline]
+    pub fn print_delimiter(mut self, delimiter: char) -> Self {
+        self.picker_config.print_delimiter = delimiter;
+        self
+    }
+
+    /// Whether or not to exit immediately if there are no items (default: `false`), similar to
+    /// `fzf --exit-0`.
+    ///
+    /// If enabled, the picker returns `Ok(None)` as soon as every [`Injector`] has been dropped
+    /// without adding any items to the picker, instead of waiting for input on an empty screen.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new().exit_if_empty(true).picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn exit_if_empty(mut self, exit_if_empty: bool) -> Self {
+        self.picker_config.exit_if_empty = exit_if_empty;
+        self
+    }
+
+    /// Whether or not to wrap items which are too wide to fit on the screen (default: `false`).
+    ///
+    /// By default, each line of an item which does not fit on the screen is truncated, and
+    /// displayed with an ellipsis. If wrapping is enabled, such lines are instead split across
+    /// multiple rows of the screen. Lines are wrapped at the last character which fits on the row,
+    /// rather than at word boundaries.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions};
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new().wrap(true).picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.picker_config.wrap = wrap;
+        self
+    }
+
+    /// Which end of an item to truncate when it is too wide to fit on the screen (default:
+    /// [`Truncate::End`]). See [`Truncate`] for more detail.
+    ///
+    /// This has no effect if [wrapping](PickerOptions::wrap) is enabled.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions, Truncate};
+    ///
+    /// // keep the file names visible
+    /// let picker: Picker<String, _> = PickerOptions::new()
+    ///     .truncate(Truncate::Start)
+    ///     .picker(StrRenderer);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn truncate(mut self, truncate: Truncate) -> Self {
+        self.picker_config.truncate = truncate;
+        self
+    }
+
+    /// How to compute the display width of text. See [`WidthPolicy`] for more detail.
+    #[must_use]
+    #[inline]
+    pub fn width_policy(mut self, width_policy: WidthPolicy) -> Self {
+        self.picker_config.width_policy = width_policy;
+        self
+    }
+
     /// How to treat case mismatch.
     #[must_use]
     #[inline]
@@ -375,6 +1223,37 @@ impl PickerOptions {
     }
 }
 
+/// The output handle on which the interactive picker screen is rendered.
+///
+/// Pass a target to [`Picker::pick_on`]. By default, [`Picker::pick`] renders to
+/// [`Target::Stderr`].
+#[derive(Debug)]
+pub enum Target {
+    /// Render to stdout.
+    Stdout,
+    /// Render to stderr.
+    Stderr,
+    /// Render to a file, which must be an interactive terminal such as the handle obtained by
+    /// opening `/dev/tty`.
+    File(File),
+    /// Render directly to the controlling terminal, by opening `/dev/tty` on Unix or `CONOUT$`
+    /// on Windows. This works even if both stdout and stderr are redirected, and does not lock
+    /// either of them.
+    Tty,
+}
+
+/// A closure which adds items to a picker using the provided injector.
+type Producer<T, R> = Box<dyn FnMut(Injector<T, R>) + Send>;
+
+/// A closure which adds items to a picker for a given query.
+type QueryProducer<T, R> = Box<dyn FnMut(&str, Injector<T, R>) + Send>;
+
+/// A query producer, along with the interval to wait for the query to stop changing.
+struct QuerySource<T: Send + Sync + 'static, R> {
+    producer: QueryProducer<T, R>,
+    debounce: Duration,
+}
+
 /// A fuzzy matching interactive item picker.
 ///
 /// The parameter `T` is the item type and the parameter `R` is the [renderer](Render), which describes how
@@ -398,6 +1277,27 @@ pub struct Picker<T: Send + Sync + 'static, R> {
     picker_config: PickerConfig,
     config: nc::Config,
     query: String,
+    /// Whether or not the injectors of the current engine are still connected.
+    connected: Arc<Connection>,
+    /// The number of items added by the injectors of the current engine.
+    progress: Progress,
+    /// The number of times the items were removed, since the addresses of removed items may be
+    /// reused by new items.
+    generation: u64,
+    /// Whether or not the open picker was cancelled by a [`CancelHandle`].
+    cancel: Arc<CancelState>,
+    /// The state of the open picker, observed by a [`PickerState`].
+    state: Arc<SharedState>,
+    /// The producer which adds the items again when the picker is reloaded.
+    reload: Option<Producer<T, R>>,
+    /// The producer which replaces the items when the query changes.
+    query_source: Option<QuerySource<T, R>>,
+}
+
+impl<T: Send + Sync + 'static, R> Drop for Picker<T, R> {
+    fn drop(&mut self) {
+        self.connected.disconnect();
+    }
 }
 
 impl<T: Send + Sync + 'static, R: Render<T>> Extend<T> for Picker<T, R> {
@@ -421,6 +1321,11 @@ impl<T: Send + Sync + 'static, R: Render<T>> Picker<T, R> {
         Duration::from_millis(16)
     }
 
+    /// The time to wait for the matcher engine in each frame, in milliseconds.
+    fn tick_timeout_millis(&self) -> u64 {
+        u64::try_from(self.picker_config.tick_timeout.as_millis()).unwrap_or(u64::MAX)
+    }
+
     /// Update the default query string. This is mainly useful for modifying the query string
     /// before re-using the [`Picker`].
     ///
@@ -434,15 +1339,180 @@ impl<T: Send + Sync + 'static, R: Render<T>> Picker<T, R> {
     /// Update the internal nucleo configuration.
     #[inline]
     pub fn update_config(&mut self, config: nc::Config) {
-        self.matcher.update_config(config);
+        self.matcher.update_config(config.clone());
+        self.config = config;
+    }
+
+    /// Change the number of threads used by the internal matching engine. See
+    /// [`PickerOptions::threads`] for more detail.
+    ///
+    /// The number of threads cannot be changed while the matcher engine is running, so this
+    /// replaces the matcher engine, which removes all of the items and disconnects all active
+    /// injectors as with [`Picker::restart`].
+    ///
+    /// ## Example
+    /// ```
+    /// use std::num::NonZero;
+    ///
+    /// use nucleo_picker::{render::StrRenderer, Picker};
+    ///
+    /// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+    ///
+    /// // a single thread is enough for a small number of items
+    /// picker.set_threads(NonZero::new(1));
+    /// picker.extend(["foo", "bar"].map(String::from));
+    /// ```
+    pub fn set_threads(&mut self, threads: Option<NonZero<usize>>) {
+        self.connected.disconnect();
+        self.connected = Arc::new(Connection::new());
+        self.matcher = new_matcher(self.config.clone(), threads);
+        self.progress = Progress::default();
+        self.generation += 1;
     }
 
     /// Restart the matcher engine, disconnecting all active injectors.
     ///
     /// Internally, this is a call to [`Nucleo::restart`] with `clear_snapshot = true`.
     /// See the documentation for [`Nucleo::restart`] for more detail.
+    ///
+    /// Disconnected injectors can be detected with [`Injector::try_push`].
     pub fn restart(&mut self) {
+        self.connected.disconnect();
+        self.connected = Arc::new(Connection::new());
         self.matcher.restart(true);
+        self.progress = Progress::default();
+        self.generation += 1;
+    }
+
+    /// Register a producer which adds the items to the picker, and which is called again with a
+    /// fresh [`Injector`] each time the picker is [reloaded](Picker::reload). This replaces any
+    /// previously registered producer.
+    ///
+    /// The producer is not called by this method, so the items should also be added initially,
+    /// for instance by calling [`Picker::reload`]. The producer is called on the thread running
+    /// the picker, so any slow work should be done on a background thread.
+    ///
+    /// While the picker is open, a reload can be triggered by a key bound to
+    /// [`Action::Reload`](bind::Action::Reload).
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use std::thread;
+    ///
+    /// use nucleo_picker::{bind::KeyBindings, render::StrRenderer, PickerOptions};
+    ///
+    /// let mut keybindings = KeyBindings::default();
+    /// keybindings.bind_str("ctrl-r", "reload").unwrap();
+    ///
+    /// let mut picker = PickerOptions::new()
+    ///     .keybindings(keybindings)
+    ///     .picker(StrRenderer);
+    ///
+    /// picker.set_reload(|injector| {
+    ///     thread::spawn(move || {
+    ///         for entry in std::fs::read_dir(".").into_iter().flatten().flatten() {
+    ///             injector.push(entry.file_name().to_string_lossy().into_owned());
+    ///         }
+    ///     });
+    /// });
+    /// picker.reload();
+    ///
+    /// let choice = picker.pick()?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn set_reload<F: FnMut(Injector<T, R>) + Send + 'static>(&mut self, reload: F) {
+        self.reload = Some(Box::new(reload));
+    }
+
+    /// Restart the matcher engine as with [`Picker::restart`], and call the producer registered
+    /// with [`Picker::set_reload`] with a fresh [`Injector`]. The query is not changed.
+    ///
+    /// Returns `false` and does nothing if no producer is registered.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker};
+    ///
+    /// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+    /// assert!(!picker.reload());
+    ///
+    /// picker.set_reload(|injector| injector.push("item".to_owned()));
+    /// assert!(picker.reload());
+    /// assert!(!picker.is_loading());
+    /// ```
+    pub fn reload(&mut self) -> bool {
+        if self.reload.is_none() {
+            return false;
+        }
+
+        self.restart();
+        let injector = self.injector();
+        if let Some(reload) = self.reload.as_mut() {
+            reload(injector);
+        }
+        true
+    }
+
+    /// Register a producer which replaces the items whenever the query changes, similar to
+    /// reloading `fzf` with the `change` event. This can be used to implement a "live grep"
+    /// interface, where the items are the output of a search command which is run with the
+    /// contents of the prompt.
+    ///
+    /// When the picker is opened, and whenever the query has stopped changing for the `debounce`
+    /// interval, the matcher engine is restarted as with [`Picker::restart`], and the producer is
+    /// called with the contents of the prompt and a fresh [`Injector`]. Restarting disconnects
+    /// the injector passed to the previous call, so the previous producer should stop as soon as
+    /// [`Injector::try_push`] fails. The producer is called on the thread running the picker, so
+    /// any slow work should be done on a background thread.
+    ///
+    /// The items are still matched against the query as usual. This replaces any previously
+    /// registered query source.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use std::{
+    ///     io::BufReader,
+    ///     process::{Command, Stdio},
+    ///     time::Duration,
+    /// };
+    ///
+    /// use nucleo_picker::{render::StrRenderer, Picker};
+    ///
+    /// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+    ///
+    /// picker.set_query_source(Duration::from_millis(100), |query, injector| {
+    ///     if let Ok(child) = Command::new("rg")
+    ///         .args(["--line-number", "--", query])
+    ///         .stdout(Stdio::piped())
+    ///         .spawn()
+    ///     {
+    ///         injector.spawn_lines(BufReader::new(child.stdout.unwrap()));
+    ///     }
+    /// });
+    ///
+    /// let choice = picker.pick()?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn set_query_source<F: FnMut(&str, Injector<T, R>) + Send + 'static>(
+        &mut self,
+        debounce: Duration,
+        source: F,
+    ) {
+        self.query_source = Some(QuerySource {
+            producer: Box::new(source),
+            debounce,
+        });
+    }
+
+    /// Restart the matcher engine and call the query source with the provided query.
+    fn run_query_source(&mut self, query: &str) {
+        self.restart();
+        let injector = self.injector();
+        if let Some(source) = self.query_source.as_mut() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(query, "running query source");
+            (source.producer)(query, injector);
+        }
     }
 
     /// Restart the matcher engine, disconnecting all active injectors and replacing the internal
@@ -454,10 +1524,53 @@ impl<T: Send + Sync + 'static, R: Render<T>> Picker<T, R> {
         self.render = render.into();
     }
 
+    /// Whether or not more items may be added to the picker, which is the case as long as any
+    /// [`Injector`] for the picker has not been dropped. Drop every injector once all of the items
+    /// have been added to signal that loading is complete.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker};
+    ///
+    /// let picker: Picker<String, _> = Picker::new(StrRenderer);
+    /// assert!(!picker.is_loading());
+    ///
+    /// let injector = picker.injector();
+    /// assert!(picker.is_loading());
+    ///
+    /// injector.push("item".to_owned());
+    /// drop(injector);
+    /// assert!(!picker.is_loading());
+    /// ```
+    #[must_use]
+    pub fn is_loading(&self) -> bool {
+        self.matcher.active_injectors() > 0
+    }
+
+    /// Get a [`CancelHandle`] which can be used to close the picker from another thread.
+    #[must_use]
+    pub fn cancel_handle(&self) -> CancelHandle {
+        CancelHandle::new(self.cancel.clone())
+    }
+
+    /// Get a [`PickerState`] which can be used to observe the state of the picker from another
+    /// thread while it is open.
+    #[must_use]
+    pub fn state(&self) -> PickerState {
+        PickerState::new(self.state.clone())
+    }
+
     /// Get an [`Injector`] to send items to the picker.
     #[must_use]
     pub fn injector(&self) -> Injector<T, R> {
-        Injector::new(self.matcher.injector(), self.render.clone())
+        Injector::new(
+            self.matcher.injector(),
+            self.render.clone(),
+            self.connected.clone(),
+            self.picker_config.sanitize_rendered,
+            self.progress.clone(),
+            self.cancel.clone(),
+        )
     }
 
     /// A convenience method to obtain the rendered version of an item as it would appear in the
@@ -470,8 +1583,101 @@ impl<T: Send + Sync + 'static, R: Render<T>> Picker<T, R> {
         self.render.render(item)
     }
 
+    /// Match the items against the query without opening the interactive picker, and return the
+    /// matched items in order of decreasing score. This is useful to implement a non-interactive
+    /// mode, similar to `fzf --filter`.
+    ///
+    /// The query is interpreted in the same way as in the interactive picker. This method blocks
+    /// until all [`Injector`]s for the picker have been dropped, and all of the items have been
+    /// processed by the matcher.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker};
+    ///
+    /// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+    /// picker.extend(["foo", "bar", "baz"].map(String::from));
+    ///
+    /// let mut matches: Vec<&String> = picker.filter("ba").collect();
+    /// matches.sort();
+    /// assert_eq!(matches, ["bar", "baz"]);
+    /// ```
+    pub fn filter(
+        &mut self,
+        query: &str,
+    ) -> impl ExactSizeIterator<Item = &T> + DoubleEndedIterator + '_ {
+        let mut query = query.to_owned();
+        normalize_query_string(&mut query);
+        self.reparse(&query);
+
+        // wait until no more items can be added
+        while self.matcher.active_injectors() > 0 {
+            self.matcher.tick(10);
+            sleep(Duration::from_millis(1));
+        }
+
+        // process the remaining items
+        while self.matcher.tick(10).running {}
+
+        let snapshot = self.matcher.snapshot();
+        snapshot
+            .matched_items(..snapshot.matched_item_count())
+            .map(|item| item.data)
+    }
+
+    /// Match the items which have been added so far against the default query, and return the
+    /// matched items along with their scores in order of decreasing score. Higher scores
+    /// correspond to better matches.
+    ///
+    /// The default query is set by [`PickerOptions::query`] or [`Picker::update_query`]. Unlike
+    /// [`Picker::filter`], this method does not wait for the [`Injector`]s to be dropped: items
+    /// which are added after this method is called are not included in the matches.
+    ///
+    /// ## Example
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, PickerOptions};
+    ///
+    /// let mut picker = PickerOptions::new().query("ba").picker(StrRenderer);
+    /// picker.extend(["foo", "bar", "baz"].map(String::from));
+    ///
+    /// let mut matches: Vec<&String> = picker.matches().map(|(item, _)| item).collect();
+    /// matches.sort();
+    /// assert_eq!(matches, ["bar", "baz"]);
+    /// ```
+    pub fn matches(&mut self) -> impl ExactSizeIterator<Item = (&T, u32)> + '_ {
+        let query = self.query.clone();
+        self.reparse(&query);
+
+        while self.matcher.tick(10).running {}
+
+        let mut matcher = nc::Matcher::new(self.config.clone());
+        let snapshot = self.matcher.snapshot();
+        snapshot
+            .matched_items(..snapshot.matched_item_count())
+            .map(move |item| {
+                let score = snapshot
+                    .pattern()
+                    .score(item.matcher_columns, &mut matcher)
+                    .unwrap_or(0);
+                (item.data, score)
+            })
+    }
+
+    /// Replace the pattern of the matcher engine with the provided query.
+    fn reparse(&mut self, query: &str) {
+        self.matcher.pattern.reparse(
+            0,
+            query,
+            self.picker_config.case_matching,
+            self.picker_config.normalization,
+            false,
+        );
+    }
+
     /// Open the interactive picker prompt and return the picked item, if any.
     ///
+    /// This is the same as calling [`Picker::pick_on`] with [`Target::Stderr`].
+    ///
     /// ## Stderr lock
     /// The picker prompt is rendered in an alternate screen using the `stderr` file handle. In
     /// order to prevent screen corruption, a lock is acquired to `stderr`; see
@@ -480,40 +1686,300 @@ impl<T: Send + Sync + 'static, R: Render<T>> Picker<T, R> {
     /// In particular, while the picker is interactive, any other thread which attempts to write to
     /// stderr will block. Note that `stdin` and `stdout` will remain fully interactive.
     ///
+    /// ## Keyboard input
+    /// Keyboard events are read from `stdin` if it is interactive, and otherwise directly from the
+    /// controlling terminal (such as `/dev/tty` on Unix). In particular, `stdin` can be piped
+    /// into your application to read items, as in the [`fzf`
+    /// example](https://github.com/autobib/nucleo-picker/tree/master/examples/fzf.rs).
+    ///
     /// # Errors
     /// Underlying IO errors from the standard library or [`crossterm`] will be propogated.
     ///
-    /// This fails with an [`io::ErrorKind::Other`] if:
+    /// This fails with an error containing a [`PickError`], which can be recovered with
+    /// [`PickError::from_io_error`], if:
     ///
     /// 1. stderr is not interactive, in which case the message will be `"is not interactive"`
     /// 2. the user presses `CTRL-C`, in which case the message will be `"keyboard interrupt"`
+    /// 3. the picker is closed by a [`CancelHandle`], in which case the message will be
+    ///    `"cancelled"`
+    /// 4. writing a frame to the terminal fails, in which case the error has the kind of the
+    ///    underlying error
     pub fn pick(&mut self) -> Result<Option<&T>, io::Error> {
-        let stderr = io::stderr().lock();
-        if stderr.is_terminal() {
-            self.pick_inner(Self::default_frame_interval(), BufWriter::new(stderr))
-        } else {
-            Err(io::Error::new(io::ErrorKind::Other, "is not interactive"))
+        self.pick_on(Target::Stderr)
+    }
+
+    /// Open the interactive picker prompt on the provided [`Target`] and return the picked item,
+    /// if any.
+    ///
+    /// The output is buffered, and if the target is [`Target::Stdout`] or [`Target::Stderr`], a
+    /// lock is acquired to the corresponding handle while the picker is interactive. See
+    /// [`Picker::pick`] for more detail.
+    ///
+    /// ## Example
+    /// Render the picker on stdout, for instance if stderr is redirected to a log file.
+    /// ```no_run
+    /// use nucleo_picker::{render::StrRenderer, Picker, Target};
+    ///
+    /// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+    /// let choice = picker.pick_on(Target::Stdout)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    /// Render the picker on the controlling terminal, for instance if both stdout and stderr are
+    /// redirected.
+    /// ```no_run
+    /// use nucleo_picker::{render::StrRenderer, Picker, Target};
+    ///
+    /// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+    /// let choice = picker.pick_on(Target::Tty)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// This fails for the same reasons as [`Picker::pick`], except that the `"is not
+    /// interactive"` error is returned if the provided target is not interactive. If the target
+    /// is [`Target::Tty`], the error from opening the controlling terminal is propagated if there
+    /// is no controlling terminal.
+    pub fn pick_on(&mut self, target: Target) -> Result<Option<&T>, io::Error> {
+        Ok(self.pick_match(target)?.map(|pick| pick.item()))
+    }
+
+    /// Open the interactive picker prompt on the provided [`Target`] and return the picked item
+    /// along with information about the match, such as the score and the matched indices. See
+    /// [`Pick`] for more detail.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use nucleo_picker::{render::StrRenderer, Picker, Target};
+    ///
+    /// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+    /// if let Some(pick) = picker.pick_match(Target::Stderr)? {
+    ///     println!("{} (score {})", pick.item(), pick.score());
+    ///     if let Some(index) = pick.index() {
+    ///         println!("added as item {index}");
+    ///     }
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// This fails for the same reasons as [`Picker::pick_on`].
+    pub fn pick_match(&mut self, target: Target) -> Result<Option<Pick<'_, T>>, io::Error> {
+        Ok(self
+            .pick_outcome(target)?
+            .selection
+            .and_then(Selection::into_pick))
+    }
+
+    /// Open the interactive picker prompt on the provided [`Target`] and return the [`Outcome`],
+    /// which contains the [`Selection`] as well as the contents of the prompt when the picker
+    /// exited. The prompt contents are returned even if nothing was selected.
+    ///
+    /// The selection is either a picked item, or the contents of the prompt if they were accepted
+    /// with the [`SelectOrQuery`](bind::Action::SelectOrQuery) or
+    /// [`AcceptQuery`](bind::Action::AcceptQuery) actions. Since these actions are not bound by
+    /// default, only use them with this method: the other methods to pick an item return `None`
+    /// if the contents of the prompt are accepted.
+    ///
+    /// ## Example
+    /// Select an existing entry, or create a new entry named after the query.
+    /// ```no_run
+    /// use nucleo_picker::{
+    ///     bind::KeyBindings, render::StrRenderer, Picker, PickerOptions, Selection, Target,
+    /// };
+    ///
+    /// let mut keybindings = KeyBindings::default();
+    /// keybindings.bind_str("enter", "select-or-query").unwrap();
+    /// keybindings.bind_str("alt-enter", "accept-query").unwrap();
+    ///
+    /// let mut picker: Picker<String, _> = PickerOptions::new()
+    ///     .keybindings(keybindings)
+    ///     .picker(StrRenderer);
+    ///
+    /// match picker.pick_outcome(Target::Stderr)?.selection {
+    ///     Some(Selection::Picked(pick)) => println!("Picked {}", pick.item()),
+    ///     Some(Selection::Custom(query)) => println!("Create new entry {query}"),
+    ///     None => println!("Cancelled"),
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// This fails for the same reasons as [`Picker::pick_on`].
+    pub fn pick_outcome(&mut self, target: Target) -> Result<Outcome<'_, T>, io::Error> {
+        let writer = BufWriter::new(TargetWriter::open(target, self.picker_config.tty_fallback)?);
+        self.pick_inner(Self::default_frame_interval(), writer, false)
+    }
+
+    /// Open the interactive picker prompt on the alternate screen held by a [`TerminalGuard`],
+    /// and return the picked item along with the contents of the prompt. See
+    /// [`Picker::pick_outcome`] for more detail.
+    ///
+    /// Unlike the other pick methods, the terminal is not restored when the picker closes; it
+    /// remains on the alternate screen until the guard is dropped, so the picker can be opened
+    /// again without the screen flashing. If [`PickerOptions::inline`] is set, the picker is
+    /// rendered at the top of the screen.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use nucleo_picker::{render::StrRenderer, Picker, Target, TerminalGuard};
+    ///
+    /// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+    /// picker.extend(["foo", "bar"].map(String::from));
+    ///
+    /// let mut guard = TerminalGuard::new(Target::Stderr)?;
+    /// let choice = picker
+    ///     .pick_in(&mut guard)?
+    ///     .selection
+    ///     .and_then(|selection| selection.into_pick())
+    ///     .map(|pick| pick.item().clone());
+    ///
+    /// // the picker can be opened again on the same screen
+    /// picker.update_query("ba");
+    /// let other = picker.pick_in(&mut guard)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// This fails for the same reasons as [`Picker::pick`], except that the `"is not
+    /// interactive"` error is returned when the guard is created.
+    pub fn pick_in(&mut self, guard: &mut TerminalGuard) -> Result<Outcome<'_, T>, io::Error> {
+        self.pick_inner(Self::default_frame_interval(), guard.writer(), true)
+    }
+
+    /// Print the rendered selection to stdout, followed by the delimiter.
+    fn print_selection(&self, selection: &Selection<'_, T>) -> Result<(), io::Error> {
+        let mut stdout = io::stdout().lock();
+        match selection {
+            Selection::Picked(pick) => {
+                stdout.write_all(self.render.render(pick.item()).as_ref().as_bytes())?;
+            }
+            Selection::Custom(query) => stdout.write_all(query.as_bytes())?,
         }
+        write!(stdout, "{}", self.picker_config.print_delimiter)?;
+        stdout.flush()
     }
 
-    /// The actual picker implementation.
+    /// Restore the terminal after the picker is closed. The terminal is always fully restored if
+    /// a termination signal was received, even if it is held by a guard or if the picker should
+    /// remain on the alternate screen, since the process is about to be terminated.
+    fn restore_terminal<W: Write>(
+        &self,
+        writer: &mut W,
+        term: &Compositor<'_>,
+        guarded: bool,
+        signalled: bool,
+    ) -> Result<(), io::Error> {
+        if signalled || !guarded {
+            disable_raw_mode()?;
+        }
+        execute!(writer, DisableBracketedPaste)?;
+        if self.picker_config.mouse {
+            execute!(writer, DisableMouseCapture)?;
+        }
+        if self.picker_config.inline_height.is_some() {
+            term.finish_inline(writer, self.picker_config.clear_on_exit)?;
+        } else if signalled || (!guarded && self.picker_config.clear_on_exit) {
+            execute!(writer, LeaveAlternateScreen)?;
+        }
+        Ok(())
+    }
+
+    /// The selection corresponding to the match with the given index.
+    fn selected_pick(&self, index: u32, matcher: &mut nc::Matcher) -> Selection<'_, T> {
+        let snapshot = self.matcher.snapshot();
+        Selection::Picked(Pick::new(
+            snapshot.get_matched_item(index).unwrap(),
+            snapshot,
+            matcher,
+        ))
+    }
+
+    /// The actual picker implementation. If `guarded` is true, the terminal is already in raw
+    /// mode on the alternate screen, and it is only restored if a termination signal is received.
     fn pick_inner<W: Write>(
         &mut self,
         interval: Duration,
         mut writer: W,
-    ) -> Result<Option<&T>, io::Error> {
-        let mut term = Compositor::new(size()?, &self.picker_config);
-        term.set_prompt(&self.query);
+        guarded: bool,
+    ) -> Result<Outcome<'_, T>, io::Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("pick", guarded).entered();
 
+        let screen = size()?;
         let mut buffer = CompositorBuffer::new();
         let mut matcher = nucleo::Matcher::new(self.config.clone());
 
-        enable_raw_mode()?;
-        execute!(writer, EnterAlternateScreen, EnableBracketedPaste)?;
+        let signals = SignalHandlers::install(self.picker_config.restore_on_signal)?;
 
+        let top = if guarded {
+            // clear anything drawn on the screen by a previous picker
+            execute!(writer, Clear(ClearType::All))?;
+            0
+        } else {
+            enable_raw_mode()?;
+            match self.picker_config.inline_height {
+                Some(inline_height) => reserve_rows(&mut writer, inline_height.min(screen.1))?,
+                None => {
+                    execute!(writer, EnterAlternateScreen)?;
+                    0
+                }
+            }
+        };
+        execute!(writer, EnableBracketedPaste)?;
+        if self.picker_config.mouse {
+            execute!(writer, EnableMouseCapture)?;
+        }
+
+        // the compositor keeps its own copy of the configuration, so that the item producers can
+        // restart the matcher engine while the picker is open
+        let config = self.picker_config.clone();
+        let mut term = Compositor::new(screen, top, &config);
+        term.set_prompt(&self.query);
+
+        // the pattern and the matches may have been changed by a previous pick
+        self.matcher.pattern.reparse(
+            0,
+            &term.pattern_text(),
+            term.case_matching(),
+            self.picker_config.normalization,
+            false,
+        );
+        term.update(
+            nc::Status {
+                changed: true,
+                running: false,
+            },
+            self.matcher.snapshot(),
+            self.is_loading(),
+            self.generation,
+        );
+
+        // the query most recently passed to the query source, and the time at which the query
+        // changed if the query source has not yet been called with the new query
+        let mut source_query = None;
+        let mut query_changed = None;
+        if self.query_source.is_some() {
+            self.run_query_source(term.prompt_contents());
+            source_query = Some(term.prompt_contents().to_owned());
+        }
+
+        // a cancellation only applies to the picker while it is open
+        let _open = self.cancel.open();
+
+        let mut key = None;
         let selection = loop {
             let deadline = Instant::now() + interval;
 
+            // quit if a termination signal was received so that the terminal can be restored
+            if signals.is_received() {
+                break Ok(None);
+            }
+
+            // quit if the picker was cancelled
+            if self.cancel.is_cancelled() {
+                break Err(PickError::Cancelled.into());
+            }
+
             // process any queued keyboard events and reset pattern if necessary
             match term.handle() {
                 Ok(summary) => match summary {
@@ -521,51 +1987,312 @@ impl<T: Send + Sync + 'static, R: Render<T>> Picker<T, R> {
                     EventSummary::UpdatePrompt(append) => {
                         self.matcher.pattern.reparse(
                             0,
-                            term.prompt_contents(),
-                            self.picker_config.case_matching,
+                            &term.pattern_text(),
+                            term.case_matching(),
                             self.picker_config.normalization,
                             append,
                         );
+                        if self.query_source.is_some() {
+                            query_changed = Some(Instant::now());
+                        }
                     }
                     EventSummary::Select => {
                         if let Some(index) = term.selection() {
-                            break Ok(Some(
-                                self.matcher
-                                    .snapshot()
-                                    .get_matched_item(index)
-                                    .unwrap()
-                                    .data,
-                            ));
+                            break Ok(Some(self.selected_pick(index, &mut matcher)));
                         }
                     }
+                    EventSummary::Expect(expected) => {
+                        key = Some(expected);
+                        break Ok(term
+                            .selection()
+                            .map(|index| self.selected_pick(index, &mut matcher)));
+                    }
+                    EventSummary::AcceptQuery => {
+                        break Ok(Some(Selection::Custom(term.prompt_contents().to_owned())));
+                    }
                     EventSummary::Quit => {
                         break Ok(None);
                     }
+                    EventSummary::Reload => {
+                        self.reload();
+                    }
                 },
                 // capture the internal error, so we can still attempt to clean up the terminal
                 // afterwards
                 Err(err) => break Err(err),
             };
 
+            // rerun the query source once the query has stopped changing
+            if let (Some(changed), Some(source)) = (query_changed, &self.query_source) {
+                if changed.elapsed() >= source.debounce {
+                    query_changed = None;
+                    if source_query.as_deref() != Some(term.prompt_contents()) {
+                        self.run_query_source(term.prompt_contents());
+                        source_query = Some(term.prompt_contents().to_owned());
+                    }
+                }
+            }
+
+            // quit if there was no input for too long
+            if self
+                .picker_config
+                .timeout
+                .is_some_and(|timeout| term.idle_time() >= timeout)
+            {
+                break Ok(None);
+            }
+
+            // check for injectors before ticking, so that any items which were added by the
+            // injectors are processed by the tick
+            let no_injectors = self.matcher.active_injectors() == 0;
+
+            // remember the selected item before the snapshot is updated
+            term.track(self.matcher.snapshot());
+
             // increment the matcher and update state
-            let status = self.matcher.tick(10);
-            term.update(status.changed, self.matcher.snapshot());
+            let status = self.matcher.tick(self.tick_timeout_millis());
+            #[cfg(feature = "tracing")]
+            tracing::trace!(
+                changed = status.changed,
+                running = status.running,
+                matched = self.matcher.snapshot().matched_item_count(),
+                items = self.matcher.snapshot().item_count(),
+                "tick"
+            );
+            term.update(
+                status,
+                self.matcher.snapshot(),
+                !no_injectors,
+                self.generation,
+            );
+
+            // quit if there are no items, and no more items can be added
+            if self.picker_config.exit_if_empty
+                && no_injectors
+                && !status.running
+                && self.matcher.snapshot().item_count() == 0
+            {
+                break Ok(None);
+            }
 
             // redraw the screen
-            term.draw(
-                &mut writer,
-                &mut matcher,
-                self.render.as_ref(),
-                self.matcher.snapshot(),
-                &mut buffer,
-            )?;
+            let redrawn = term
+                .draw(
+                    &mut writer,
+                    &mut matcher,
+                    self.render.as_ref(),
+                    self.matcher.snapshot(),
+                    &mut buffer,
+                )
+                .map_err(PickError::Draw)?;
+
+            // notify any observers of the state
+            self.state.publish(term.prompt_contents(), term.status());
+            if let Some(on_frame) = &self.picker_config.on_frame {
+                on_frame.call(FrameInfo {
+                    status: term.status(),
+                    redrawn,
+                    elapsed: (deadline - interval).elapsed(),
+                });
+            }
+
+            #[cfg(feature = "tracing")]
+            tracing::trace!(elapsed = ?(deadline - interval).elapsed(), "frame");
 
             // wait if frame rendering finishes early
             sleep(deadline - Instant::now());
         };
 
-        disable_raw_mode()?;
-        execute!(writer, DisableBracketedPaste, LeaveAlternateScreen)?;
-        selection
+        self.state.close();
+
+        // the signal handlers must be uninstalled even if the terminal cannot be restored, so
+        // that a received signal still terminates the process
+        let restored = self.restore_terminal(&mut writer, &term, guarded, signals.is_received());
+        let finished = signals.finish();
+        restored?;
+        finished?;
+
+        let selection = selection?;
+        if self.picker_config.print_on_exit && !guarded {
+            if let Some(selection) = &selection {
+                self.print_selection(selection)?;
+            }
+        }
+
+        Ok(Outcome {
+            selection,
+            key,
+            query: term.prompt_contents().to_owned(),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use std::{fmt, num::NonZero, time::Duration};
+
+    use nucleo::pattern::{CaseMatching, Normalization};
+    use serde::{
+        de::{Error, MapAccess, Visitor},
+        Deserialize, Deserializer,
+    };
+
+    use super::{bind::KeyBindings, ColorMode, PickerOptions, Truncate, WidthPolicy};
+
+    const FIELDS: &[&str] = &[
+        "highlight",
+        "overflow-indicators",
+        "restore-on-signal",
+        "tty-fallback",
+        "clear-on-exit",
+        "mouse",
+        "wrap",
+        "exit-if-empty",
+        "print-on-exit",
+        "loading-indicator",
+        "sticky-selection",
+        "cycle",
+        "icons",
+        "sanitize-rendered",
+        "highlight-padding",
+        "scroll-padding",
+        "prompt-padding",
+        "inline",
+        "max-item-height",
+        "min-size",
+        "threads",
+        "timeout",
+        "query",
+        "prompt-prefix",
+        "placeholder",
+        "no-match-text",
+        "case-matching",
+        "normalization",
+        "width-policy",
+        "truncate",
+        "color-mode",
+        "keybindings",
+    ];
+
+    /// Deserialize one of the given `variants` from its name, where the names are given in the
+    /// same order as the variants.
+    fn variant<'de, M, V: Copy>(
+        map: &mut M,
+        names: &'static [&'static str],
+        variants: &[V],
+    ) -> Result<V, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let name = map.next_value::<String>()?;
+        names
+            .iter()
+            .position(|candidate| *candidate == name)
+            .map(|idx| variants[idx])
+            .ok_or_else(|| M::Error::unknown_variant(&name, names))
+    }
+
+    struct PickerOptionsVisitor;
+
+    impl<'de> Visitor<'de> for PickerOptionsVisitor {
+        type Value = PickerOptions;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map of picker options")
+        }
+
+        fn visit_map<M>(self, mut map: M) -> Result<PickerOptions, M::Error>
+        where
+            M: MapAccess<'de>,
+        {
+            let mut options = PickerOptions::default();
+            while let Some(key) = map.next_key::<String>()? {
+                options = match key.as_str() {
+                    "highlight" => options.highlight(map.next_value()?),
+                    "overflow-indicators" => options.overflow_indicators(map.next_value()?),
+                    "restore-on-signal" => options.restore_on_signal(map.next_value()?),
+                    "tty-fallback" => options.tty_fallback(map.next_value()?),
+                    "clear-on-exit" => options.clear_on_exit(map.next_value()?),
+                    "mouse" => options.mouse(map.next_value()?),
+                    "wrap" => options.wrap(map.next_value()?),
+                    "exit-if-empty" => options.exit_if_empty(map.next_value()?),
+                    "print-on-exit" => options.print_on_exit(map.next_value()?),
+                    "loading-indicator" => options.loading_indicator(map.next_value()?),
+                    "sticky-selection" => options.sticky_selection(map.next_value()?),
+                    "cycle" => options.cycle(map.next_value()?),
+                    "icons" => options.icons(map.next_value()?),
+                    "sanitize-rendered" => options.sanitize_rendered(map.next_value()?),
+                    "highlight-padding" => options.highlight_padding(map.next_value()?),
+                    "scroll-padding" => options.scroll_padding(map.next_value()?),
+                    "prompt-padding" => options.prompt_padding(map.next_value()?),
+                    "inline" => options.inline(map.next_value()?),
+                    "max-item-height" => {
+                        options.max_item_height(NonZero::new(map.next_value::<u16>()?))
+                    }
+                    "min-size" => {
+                        let (width, height) = map.next_value()?;
+                        options.min_size(width, height)
+                    }
+                    "threads" => options.threads(Some(map.next_value::<NonZero<usize>>()?)),
+                    "timeout" => {
+                        let seconds = map.next_value::<f64>()?;
+                        options.timeout(Some(
+                            Duration::try_from_secs_f64(seconds).map_err(M::Error::custom)?,
+                        ))
+                    }
+                    "query" => options.query(map.next_value::<String>()?),
+                    "prompt-prefix" => options.prompt_prefix(map.next_value::<String>()?),
+                    "placeholder" => options.placeholder(map.next_value::<String>()?),
+                    "no-match-text" => options.no_match_text(map.next_value::<String>()?),
+                    "case-matching" => options.case_matching(variant(
+                        &mut map,
+                        &["smart", "ignore", "respect"],
+                        &[
+                            CaseMatching::Smart,
+                            CaseMatching::Ignore,
+                            CaseMatching::Respect,
+                        ],
+                    )?),
+                    "normalization" => options.normalization(variant(
+                        &mut map,
+                        &["smart", "never"],
+                        &[Normalization::Smart, Normalization::Never],
+                    )?),
+                    "width-policy" => options.width_policy(variant(
+                        &mut map,
+                        &["standard", "ambiguous-wide", "codepoint"],
+                        &[
+                            WidthPolicy::Standard,
+                            WidthPolicy::AmbiguousWide,
+                            WidthPolicy::Codepoint,
+                        ],
+                    )?),
+                    "truncate" => options.truncate(variant(
+                        &mut map,
+                        &["end", "start"],
+                        &[Truncate::End, Truncate::Start],
+                    )?),
+                    "color-mode" => options.color_mode(variant(
+                        &mut map,
+                        &["auto", "never"],
+                        &[ColorMode::Auto, ColorMode::Never],
+                    )?),
+                    "keybindings" => options.keybindings(map.next_value::<KeyBindings>()?),
+                    _ => return Err(M::Error::unknown_field(&key, FIELDS)),
+                };
+            }
+            Ok(options)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for PickerOptions {
+        /// Deserialize from a map of options, applied on top of the default options. This
+        /// implementation is enabled using the `serde` feature.
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_map(PickerOptionsVisitor)
+        }
     }
 }
@@ -0,0 +1,177 @@
+use std::{fmt, ptr, sync::OnceLock};
+
+use nucleo as nc;
+
+use crate::injector::rendered_column;
+
+/// An item picked from a [`Picker`](super::Picker), along with information about the match.
+///
+/// This is returned by [`Picker::pick_match`](super::Picker::pick_match).
+pub struct Pick<'a, T: Send + Sync + 'static> {
+    item: &'a T,
+    snapshot: &'a nc::Snapshot<T>,
+    score: u32,
+    indices: Vec<u32>,
+    index: OnceLock<Option<u32>>,
+}
+
+impl<'a, T: Send + Sync + 'static> Pick<'a, T> {
+    /// Compute the match information for the item.
+    pub(crate) fn new(
+        item: nc::Item<'a, T>,
+        snapshot: &'a nc::Snapshot<T>,
+        matcher: &mut nc::Matcher,
+    ) -> Self {
+        let pattern = snapshot.pattern();
+        let score = pattern.score(item.matcher_columns, matcher).unwrap_or(0);
+
+        let mut indices = Vec::new();
+        pattern
+            .column_pattern(0)
+            .indices(item.matcher_columns[0].slice(..), matcher, &mut indices);
+        indices.sort_unstable();
+        indices.dedup();
+
+        // remove the indices which correspond to keywords, since they are not rendered
+        let rendered_len = rendered_column(&item.matcher_columns[0]).len() as u32;
+        indices.retain(|idx| *idx < rendered_len);
+
+        Self {
+            item: item.data,
+            snapshot,
+            score,
+            indices,
+            index: OnceLock::new(),
+        }
+    }
+
+    /// The picked item.
+    #[must_use]
+    pub fn item(&self) -> &'a T {
+        self.item
+    }
+
+    /// The index of the item, in the order in which the items were added to the picker, or
+    /// `None` if the item is not found in the picker.
+    ///
+    /// The index is computed by searching the items in the picker the first time that it is
+    /// requested, which requires time proportional to the number of items, and is cached for
+    /// later calls.
+    #[must_use]
+    pub fn index(&self) -> Option<u32> {
+        // the matcher engine does not expose the index of matched items, but the items are never
+        // moved, so we can find the index by comparing addresses
+        *self.index.get_or_init(|| {
+            (0..self.snapshot.item_count()).find(|idx| {
+                self.snapshot
+                    .get_item(*idx)
+                    .is_some_and(|candidate| ptr::eq(candidate.data, self.item))
+            })
+        })
+    }
+
+    /// The score of the match. Higher scores correspond to better matches.
+    #[must_use]
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    /// The indices of the characters in the rendered item which were matched by the query, in
+    /// increasing order.
+    ///
+    /// The indices count the grapheme clusters of the rendered item (as returned by
+    /// [`Render::render`](super::Render::render)), rather than bytes.
+    #[must_use]
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+}
+
+/// What was selected when the picker was closed.
+#[derive(Debug)]
+pub enum Selection<'a, T: Send + Sync + 'static> {
+    /// An item was picked.
+    Picked(Pick<'a, T>),
+    /// The contents of the prompt were accepted instead of an item, using the
+    /// [`SelectOrQuery`](crate::bind::Action::SelectOrQuery) or
+    /// [`AcceptQuery`](crate::bind::Action::AcceptQuery) actions.
+    Custom(String),
+}
+
+impl<'a, T: Send + Sync + 'static> Selection<'a, T> {
+    /// The picked item, or `None` if the contents of the prompt were accepted instead.
+    #[must_use]
+    pub fn into_pick(self) -> Option<Pick<'a, T>> {
+        match self {
+            Self::Picked(pick) => Some(pick),
+            Self::Custom(_) => None,
+        }
+    }
+}
+
+/// The result of an interactive picker session, returned by
+/// [`Picker::pick_outcome`](super::Picker::pick_outcome).
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Outcome<'a, T: Send + Sync + 'static> {
+    /// What was selected, or `None` if the picker was closed without a selection.
+    pub selection: Option<Selection<'a, T>>,
+    /// The contents of the prompt when the picker was closed.
+    pub query: String,
+    /// The key which closed the picker, if it is bound to the
+    /// [`Expect`](crate::bind::Action::Expect) action. The key is formatted in the same way as
+    /// the key specifications accepted by [`KeyBindings`](crate::bind::KeyBindings), such as
+    /// `ctrl-o`, with modifiers in the order `ctrl`, `alt`, `shift`.
+    pub key: Option<String>,
+}
+
+impl<T: Send + Sync + 'static + fmt::Debug> fmt::Debug for Pick<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pick")
+            .field("item", &self.item)
+            .field("score", &self.score)
+            .field("indices", &self.indices)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use nucleo::{Config, Matcher, Nucleo};
+
+    use super::*;
+
+    #[test]
+    fn test_index() {
+        let mut nucleo = Nucleo::new(Config::DEFAULT, Arc::new(|| {}), Some(1), 1);
+        let injector = nucleo.injector();
+        for item in ["foo", "bar", "baz"] {
+            injector.push(item, |item, cols| cols[0] = (*item).into());
+        }
+        nucleo.pattern.reparse(
+            0,
+            "ba",
+            nc::pattern::CaseMatching::Smart,
+            nc::pattern::Normalization::Smart,
+            false,
+        );
+        while nucleo.tick(10).running {}
+
+        let snapshot = nucleo.snapshot();
+        let mut matcher = Matcher::default();
+        let picks: Vec<Pick<'_, &str>> = snapshot
+            .matched_items(..)
+            .map(|item| Pick::new(item, snapshot, &mut matcher))
+            .collect();
+
+        // the index counts every item, rather than the matched items
+        let mut indices: Vec<(&str, Option<u32>)> = picks
+            .iter()
+            .map(|pick| (*pick.item(), pick.index()))
+            .collect();
+        indices.sort_unstable();
+        assert_eq!(indices, [("bar", Some(1)), ("baz", Some(2))]);
+    }
+}
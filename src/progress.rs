@@ -0,0 +1,97 @@
+use std::{
+    fmt,
+    num::NonZero,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+#[derive(Debug, Default)]
+struct Counters {
+    items: AtomicU64,
+    bytes: AtomicU64,
+}
+
+/// A handle which counts the items added to a [`Picker`](super::Picker) by its injectors.
+///
+/// This struct is cheaply clonable and can be sent across threads. Obtain a handle with
+/// [`Injector::progress`](super::Injector::progress). Unlike an injector, holding a handle does
+/// not keep the picker [loading](super::Picker::is_loading), so a handle can be used in a
+/// [status line](super::PickerOptions::status_line) or in another interface to report the
+/// progress of a long-running producer.
+///
+/// The counts are shared by every injector of the picker, and are reset when the picker is
+/// restarted; a handle obtained before a restart keeps the counts from before the restart.
+///
+/// ## Example
+/// ```
+/// use nucleo_picker::{render::StrRenderer, Picker};
+///
+/// let picker: Picker<String, _> = Picker::new(StrRenderer);
+/// let injector = picker.injector();
+/// let progress = injector.progress();
+///
+/// injector.push("foo".to_owned());
+/// injector.clone().push("bar".to_owned());
+/// assert_eq!(progress.items(), 2);
+/// assert_eq!(progress.bytes(), 6);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    counters: Arc<Counters>,
+}
+
+impl Progress {
+    /// The number of items which were added to the picker.
+    #[must_use]
+    pub fn items(&self) -> u64 {
+        self.counters.items.load(Ordering::Relaxed)
+    }
+
+    /// The total length, in bytes, of the rendered items which were added to the picker.
+    #[must_use]
+    pub fn bytes(&self) -> u64 {
+        self.counters.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Record that an item with the given rendered length was added, returning the new number of
+    /// items.
+    pub(crate) fn record(&self, bytes: usize) -> u64 {
+        self.counters
+            .bytes
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+        self.counters.items.fetch_add(1, Ordering::Relaxed) + 1
+    }
+}
+
+/// A closure which is called after every `interval` items are added.
+#[derive(Clone)]
+pub(crate) struct ProgressCallback {
+    interval: NonZero<u64>,
+    callback: Arc<dyn Fn(&Progress) + Send + Sync>,
+}
+
+impl ProgressCallback {
+    pub fn new<F: Fn(&Progress) + Send + Sync + 'static>(interval: NonZero<u64>, f: F) -> Self {
+        Self {
+            interval,
+            callback: Arc::new(f),
+        }
+    }
+
+    /// Call the closure if the number of items is a multiple of the interval.
+    pub fn notify(&self, items: u64, progress: &Progress) {
+        if items % self.interval == 0 {
+            (self.callback)(progress);
+        }
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgressCallback")
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
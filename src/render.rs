@@ -4,7 +4,13 @@
 //! appropriate types) can be used as the arguments passed to the
 //! [`PickerOptions::picker`](super::PickerOptions::picker) and [`Picker::new`](super::Picker::new)
 //! methods.
-use std::{borrow::Cow, path::Path};
+use std::{
+    borrow::Cow,
+    fmt::{Display, Write},
+    path::Path,
+};
+
+use unicode_width::UnicodeWidthStr;
 
 use super::Render;
 
@@ -63,7 +69,7 @@ impl<T: AsRef<Path>> Render<T> for PathRenderer {
     }
 }
 
-/// A renderer which uses a type's [`Display`](std::fmt::Display) implementation.
+/// A renderer which uses a type's [`Display`] implementation.
 ///
 /// ## Example
 /// ```
@@ -84,3 +90,117 @@ impl<T: ToString> Render<T> for DisplayRenderer {
         item.to_string()
     }
 }
+
+/// A renderer which formats the fields of an item into aligned columns.
+///
+/// The fields of an item are computed by a closure, which can return any iterable of types
+/// implementing [`Display`], such as an array or a [`Vec`]. Each field other than the last is
+/// padded with spaces to the width of its column, and the columns are separated by two spaces.
+/// Fields which are wider than their column are not truncated, so it is best to choose the
+/// widths to fit the widest field; this is done automatically by
+/// [`from_sample`](TabularRenderer::from_sample).
+///
+/// ## Example
+/// ```
+/// # use nucleo_picker::{render::TabularRenderer, Render};
+/// struct Package {
+///     name: &'static str,
+///     version: &'static str,
+///     description: &'static str,
+/// }
+///
+/// let packages = [
+///     Package { name: "nucleo", version: "0.5.0", description: "fuzzy matcher" },
+///     Package { name: "crossterm", version: "0.28.1", description: "terminal library" },
+/// ];
+///
+/// let renderer = TabularRenderer::from_sample(&packages, |p: &Package| {
+///     [p.name, p.version, p.description]
+/// });
+///
+/// assert_eq!(renderer.render(&packages[0]), "nucleo     0.5.0   fuzzy matcher");
+/// assert_eq!(renderer.render(&packages[1]), "crossterm  0.28.1  terminal library");
+/// ```
+pub struct TabularRenderer<F> {
+    fields: F,
+    widths: Vec<usize>,
+}
+
+impl<F> TabularRenderer<F> {
+    /// Initialize a new renderer with the given column widths, in terminal columns. Columns
+    /// without a width are not padded.
+    ///
+    /// ## Example
+    /// ```
+    /// # use nucleo_picker::{render::TabularRenderer, Render};
+    /// let renderer = TabularRenderer::new(vec![4], |(id, name): &(u32, &str)| {
+    ///     [id.to_string(), name.to_string()]
+    /// });
+    ///
+    /// assert_eq!(renderer.render(&(12, "Alice")), "12    Alice");
+    /// ```
+    pub fn new(widths: Vec<usize>, fields: F) -> Self {
+        Self { fields, widths }
+    }
+
+    /// Initialize a new renderer with column widths computed from the given sample of items, so
+    /// that each field of the sample fits in its column.
+    pub fn from_sample<'a, T: 'a, I, D>(sample: impl IntoIterator<Item = &'a T>, fields: F) -> Self
+    where
+        F: Fn(&T) -> I,
+        I: IntoIterator<Item = D>,
+        D: Display,
+    {
+        let mut widths = Vec::new();
+        let mut buffer = String::new();
+        for item in sample {
+            for (idx, field) in fields(item).into_iter().enumerate() {
+                buffer.clear();
+                // writing to a string does not fail
+                let _ = write!(buffer, "{field}");
+                let width = buffer.width();
+                match widths.get_mut(idx) {
+                    Some(current) => *current = width.max(*current),
+                    None => widths.push(width),
+                }
+            }
+        }
+        Self { fields, widths }
+    }
+
+    /// The widths of the columns, in terminal columns.
+    pub fn widths(&self) -> &[usize] {
+        &self.widths
+    }
+}
+
+impl<T, F, I, D> Render<T> for TabularRenderer<F>
+where
+    F: Fn(&T) -> I,
+    I: IntoIterator<Item = D>,
+    D: Display,
+{
+    type Str<'a>
+        = String
+    where
+        T: 'a;
+
+    fn render<'a>(&self, item: &'a T) -> Self::Str<'a> {
+        let mut rendered = String::new();
+        let mut padding = 0;
+        for (idx, field) in (self.fields)(item).into_iter().enumerate() {
+            // pad the previous field only if there is a next field, to avoid trailing whitespace
+            if idx > 0 {
+                rendered.extend(std::iter::repeat_n(' ', padding + 2));
+            }
+            let start = rendered.len();
+            // writing to a string does not fail
+            let _ = write!(rendered, "{field}");
+            padding = self
+                .widths
+                .get(idx)
+                .map_or(0, |width| width.saturating_sub(rendered[start..].width()));
+        }
+        rendered
+    }
+}
@@ -0,0 +1,95 @@
+//! # Terminal restoration on termination signals
+//! While the picker is interactive, the terminal is in raw mode and displaying the alternate
+//! screen. If the process is terminated by a signal, the terminal is left in this state. In this
+//! module, we optionally install signal handlers so that the terminal can be restored before
+//! the process exits.
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+#[cfg(unix)]
+use signal_hook::{
+    consts::{SIGHUP, SIGINT, SIGQUIT, SIGTERM},
+    flag::register_usize,
+    low_level::{emulate_default_handler, unregister},
+    SigId,
+};
+
+#[cfg(unix)]
+use crate::PickError;
+
+/// The signals which result in the terminal being restored.
+#[cfg(unix)]
+const SIGNALS: [i32; 4] = [SIGTERM, SIGHUP, SIGINT, SIGQUIT];
+
+/// Signal handlers which record the receipt of a termination signal. The handlers are
+/// uninstalled when this struct is dropped.
+pub struct SignalHandlers {
+    /// The most recently received signal, or `0` if no signal was received.
+    received: Arc<AtomicUsize>,
+    #[cfg(unix)]
+    ids: Vec<SigId>,
+}
+
+impl SignalHandlers {
+    /// Install the signal handlers if `enabled` is true. Otherwise, no handlers are installed.
+    ///
+    /// On non-Unix platforms, this never installs any handlers.
+    pub fn install(enabled: bool) -> Result<Self, io::Error> {
+        let received = Arc::new(AtomicUsize::new(0));
+
+        #[cfg(unix)]
+        {
+            let mut ids = Vec::new();
+            if enabled {
+                for signal in SIGNALS {
+                    ids.push(register_usize(signal, received.clone(), signal as usize)?);
+                }
+            }
+            Ok(Self { received, ids })
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = enabled;
+            Ok(Self { received })
+        }
+    }
+
+    /// Whether or not a termination signal was received.
+    pub fn is_received(&self) -> bool {
+        self.received.load(Ordering::SeqCst) != 0
+    }
+
+    /// Uninstall the signal handlers. If a signal was received, the process is terminated as if
+    /// the default handler for the signal was invoked, so this method only returns if no signal
+    /// was received.
+    ///
+    /// This method must only be called after the terminal is restored.
+    pub fn finish(self) -> Result<(), io::Error> {
+        #[cfg(unix)]
+        {
+            let signal = self.received.load(Ordering::SeqCst);
+            drop(self);
+            if signal != 0 {
+                emulate_default_handler(signal as i32)?;
+                return Err(PickError::Terminated.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SignalHandlers {
+    fn drop(&mut self) {
+        for id in self.ids.drain(..) {
+            unregister(id);
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use super::Status;
+
+/// The state of an open picker, as observed by a [`PickerState`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct StateSnapshot {
+    /// The contents of the prompt.
+    pub query: String,
+    /// The match counts and the selection.
+    pub status: Status,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// Incremented whenever the state changes, including when the picker closes.
+    generation: u64,
+    current: Option<StateSnapshot>,
+}
+
+/// The state shared between a [`Picker`](super::Picker) and its observers.
+#[derive(Debug, Default)]
+pub(crate) struct SharedState {
+    inner: Mutex<Inner>,
+    changed: Condvar,
+}
+
+impl SharedState {
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        // the state is always consistent, so a panic while holding the lock can be ignored
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Publish the state of the open picker, notifying observers if it changed.
+    pub fn publish(&self, query: &str, status: Status) {
+        let mut inner = self.lock();
+        if inner
+            .current
+            .as_ref()
+            .is_some_and(|current| current.status == status && current.query == query)
+        {
+            return;
+        }
+
+        inner.current = Some(StateSnapshot {
+            query: query.to_owned(),
+            status,
+        });
+        inner.generation += 1;
+        self.changed.notify_all();
+    }
+
+    /// Record that the picker closed, notifying observers.
+    pub fn close(&self) {
+        let mut inner = self.lock();
+        if inner.current.take().is_some() {
+            inner.generation += 1;
+            self.changed.notify_all();
+        }
+    }
+}
+
+/// A handle which allows observing the state of an interactive [`Picker`](super::Picker) from
+/// another thread.
+///
+/// This struct is cheaply clonable and can be sent across threads. Obtain a handle with
+/// [`Picker::state`](super::Picker::state), and either poll the state with
+/// [`current`](PickerState::current), or block until the state changes with
+/// [`wait`](PickerState::wait). This can be used to drive companion interfaces, such as a window
+/// title or an external preview process.
+///
+/// The state is updated at most once per frame, and only while the picker is open.
+///
+/// ## Example
+/// ```no_run
+/// use std::{thread, time::Duration};
+///
+/// use nucleo_picker::{render::StrRenderer, Picker};
+///
+/// let mut picker: Picker<String, _> = Picker::new(StrRenderer);
+/// let mut state = picker.state();
+///
+/// thread::spawn(move || loop {
+///     if let Some(snapshot) = state.wait(Duration::from_secs(1)) {
+///         eprintln!(
+///             "{}: {}/{}",
+///             snapshot.query, snapshot.status.matched, snapshot.status.total
+///         );
+///     }
+/// });
+///
+/// let choice = picker.pick()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct PickerState {
+    shared: Arc<SharedState>,
+    /// The generation of the state most recently returned by this handle.
+    seen: u64,
+}
+
+impl PickerState {
+    pub(crate) fn new(shared: Arc<SharedState>) -> Self {
+        Self { shared, seen: 0 }
+    }
+
+    /// The current state of the picker, or `None` if the picker is not open.
+    #[must_use]
+    pub fn current(&self) -> Option<StateSnapshot> {
+        self.shared.lock().current.clone()
+    }
+
+    /// Block until the state changes or the timeout elapses, and return the current state of the
+    /// picker, or `None` if the picker is not open.
+    ///
+    /// The state is considered changed if it has been updated since the last call to this
+    /// method on this handle, so changes which happen between calls are not missed.
+    pub fn wait(&mut self, timeout: Duration) -> Option<StateSnapshot> {
+        let inner = self.shared.lock();
+        let (inner, _) = self
+            .shared
+            .changed
+            .wait_timeout_while(inner, timeout, |inner| inner.generation == self.seen)
+            .unwrap_or_else(PoisonError::into_inner);
+        self.seen = inner.generation;
+        inner.current.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    fn status(matched: u32) -> Status {
+        Status {
+            matched,
+            total: 10,
+            selection: Some(0),
+            loading: false,
+            matching: false,
+        }
+    }
+
+    #[test]
+    fn test_publish() {
+        let shared = Arc::new(SharedState::default());
+        let mut state = PickerState::new(shared.clone());
+        assert_eq!(state.current(), None);
+
+        shared.publish("foo", status(3));
+        let snapshot = state.wait(Duration::ZERO).unwrap();
+        assert_eq!(snapshot.query, "foo");
+        assert_eq!(snapshot.status, status(3));
+
+        // no change, so the wait times out and returns the same state
+        shared.publish("foo", status(3));
+        assert_eq!(shared.lock().generation, 1);
+        assert_eq!(state.wait(Duration::ZERO), Some(snapshot));
+
+        shared.close();
+        assert_eq!(state.wait(Duration::ZERO), None);
+        assert_eq!(shared.lock().generation, 2);
+    }
+
+    #[test]
+    fn test_wait() {
+        let shared = Arc::new(SharedState::default());
+        let mut state = PickerState::new(shared.clone());
+
+        let handle = thread::spawn(move || state.wait(Duration::from_secs(10)));
+        shared.publish("bar", status(1));
+        assert_eq!(handle.join().unwrap().unwrap().query, "bar");
+    }
+}
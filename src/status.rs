@@ -0,0 +1,82 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+/// The state of the picker which is passed to a custom status line.
+///
+/// See [`PickerOptions::status_line`](super::PickerOptions::status_line) for more detail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Status {
+    /// The number of items which match the query.
+    pub matched: u32,
+    /// The total number of items in the picker.
+    pub total: u32,
+    /// The index of the selected match, where `0` is the best match, or `None` if there are no
+    /// matches.
+    pub selection: Option<u32>,
+    /// Whether or not more items may be added to the picker; see
+    /// [`Picker::is_loading`](super::Picker::is_loading).
+    pub loading: bool,
+    /// Whether or not the matcher is still matching the items against the query. This is
+    /// usually only the case briefly after the query changes, unless there are many items.
+    pub matching: bool,
+}
+
+/// Information about a frame of the picker which is passed to a frame callback.
+///
+/// See [`PickerOptions::on_frame`](super::PickerOptions::on_frame) for more detail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FrameInfo {
+    /// The state of the picker at the end of the frame.
+    pub status: Status,
+    /// Whether or not the screen was redrawn during the frame.
+    pub redrawn: bool,
+    /// The time taken to process input, update the matches, and draw the screen. This does not
+    /// include the time spent waiting for the next frame.
+    pub elapsed: Duration,
+}
+
+/// A closure which generates the text of the status line.
+#[derive(Clone)]
+pub struct StatusLine(Arc<dyn Fn(&Status) -> String + Send + Sync>);
+
+impl StatusLine {
+    pub fn new<F: Fn(&Status) -> String + Send + Sync + 'static>(f: F) -> Self {
+        Self(Arc::new(f))
+    }
+
+    pub fn text(&self, status: &Status) -> String {
+        (self.0)(status)
+    }
+}
+
+impl fmt::Debug for StatusLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StatusLine").finish_non_exhaustive()
+    }
+}
+
+/// A closure which is called once per frame.
+#[derive(Clone)]
+pub struct FrameHook(Arc<Mutex<dyn FnMut(FrameInfo) + Send>>);
+
+impl FrameHook {
+    pub fn new<F: FnMut(FrameInfo) + Send + 'static>(f: F) -> Self {
+        Self(Arc::new(Mutex::new(f)))
+    }
+
+    pub fn call(&self, info: FrameInfo) {
+        // a previous panic in the closure has already been propagated to the caller
+        (self.0.lock().unwrap_or_else(PoisonError::into_inner))(info);
+    }
+}
+
+impl fmt::Debug for FrameHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FrameHook").finish_non_exhaustive()
+    }
+}
@@ -8,42 +8,135 @@ mod editable;
 mod item;
 mod layout;
 mod span;
+mod theme;
 mod unicode;
 
 use std::{
+    borrow::Cow,
+    collections::HashMap,
     io::{self, Write},
+    iter::repeat_n,
+    mem,
+    num::NonZero,
     ops::Range,
-    time::Duration,
+    ptr,
+    time::{Duration, Instant},
 };
 
 use crossterm::{
-    cursor::{MoveRight, MoveTo, MoveToColumn, MoveToPreviousLine},
+    cursor::{position, MoveRight, MoveTo, MoveToColumn, MoveToNextLine, MoveToPreviousLine},
     event::{poll, read},
-    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor},
+    style::{
+        Attribute, Print, PrintStyledContent, ResetColor, SetAttribute, SetStyle, StyledContent,
+    },
     terminal::{BeginSynchronizedUpdate, Clear, ClearType, EndSynchronizedUpdate},
     ExecutableCommand, QueueableCommand,
 };
 use nucleo::{
-    pattern::{CaseMatching, Normalization},
+    pattern::{Atom, CaseMatching, Normalization, Pattern},
     Matcher,
 };
 
-pub use self::editable::normalize_query_string;
+pub use self::{
+    editable::{normalize_query_string, sanitize_rendered},
+    span::Truncate,
+    theme::{is_legacy_console, ColorMode, Scrollbar, Theme},
+    unicode::WidthPolicy,
+};
 use self::{
     editable::{Edit, EditableString},
-    item::RenderedItem,
+    item::{Clamped, RenderedItem, WrappedSnapshot},
     layout::{Layout, VariableSizeBuffer},
-    span::{Head, KeepLines, Spanned, Tail},
-    unicode::{AsciiProcessor, Span, UnicodeProcessor},
+    span::{Clamp, Head, KeepLines, LineStyle, Spanned, Tail},
+    unicode::{with_processor, AsciiProcessor, Processor, Span},
 };
 use crate::{
-    bind::{convert, Event},
-    // component::{Edit, EditableString},
-    Render,
+    bind::{convert, Event, KeyBindings},
+    injector::rendered_column,
+    status::{FrameHook, Status, StatusLine},
+    PickError, Render,
 };
 
 const ELLIPSIS: char = '…';
 
+/// The maximum interval between two clicks on the same item for the clicks to count as a
+/// double click.
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The frames of the loading indicator.
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// The interval between frames of the loading indicator.
+const SPINNER_INTERVAL: Duration = Duration::from_millis(80);
+
+/// The maximum number of matches searched for a sticky selection which is not near its previous
+/// position.
+const STICKY_SEARCH_LIMIT: u32 = 10_000;
+
+/// Reserve `height` rows starting at the row containing the cursor for rendering an inline
+/// picker, scrolling the terminal if there is not enough space below the cursor. Returns the
+/// index of the first reserved row.
+pub fn reserve_rows<W: Write>(writer: &mut W, height: u16) -> Result<u16, io::Error> {
+    // printing newlines, rather than moving the cursor, scrolls the terminal at the bottom of the
+    // screen
+    writer.queue(MoveToColumn(0))?;
+    for _ in 1..height {
+        writer.queue(Print("\n"))?;
+    }
+    writer.flush()?;
+
+    let (_, row) = position()?;
+    Ok((row + 1).saturating_sub(height))
+}
+
+/// Convert a query so that each atom without special syntax is matched as a substring rather than
+/// fuzzily. Conversely, atoms beginning with `'` are matched fuzzily.
+fn exact_query(query: &str) -> String {
+    let mut converted = String::with_capacity(query.len() + 4);
+    let mut saw_backslash = false;
+
+    // split on unescaped spaces, in the same way as the query parser
+    for (i, atom) in query
+        .split(|ch| {
+            let is_separator = ch == ' ' && !saw_backslash;
+            saw_backslash = ch == '\\';
+            is_separator
+        })
+        .enumerate()
+    {
+        if i > 0 {
+            converted.push(' ');
+        }
+
+        match atom.as_bytes() {
+            // empty atoms, or atoms which already have special syntax
+            [] | [b'!' | b'^', ..] | [.., b'$'] => converted.push_str(atom),
+            // a quote switches back to fuzzy matching
+            [b'\'', ..] => converted.push_str(&atom[1..]),
+            // escaped syntax characters are already literal in a substring atom
+            [b'\\', b'!' | b'^' | b'\'', ..] => {
+                converted.push('\'');
+                converted.push_str(&atom[1..]);
+            }
+            _ => {
+                converted.push('\'');
+                converted.push_str(atom);
+            }
+        }
+    }
+
+    converted
+}
+
+/// The next case matching mode when toggling case sensitivity.
+fn next_case_matching(case_matching: CaseMatching) -> CaseMatching {
+    match case_matching {
+        CaseMatching::Smart => CaseMatching::Ignore,
+        CaseMatching::Ignore => CaseMatching::Respect,
+        _ => CaseMatching::Smart,
+    }
+}
+
 /// The outcome after processing all of the events.
 pub enum EventSummary {
     /// Continue rendering the frame.
@@ -52,8 +145,61 @@ pub enum EventSummary {
     UpdatePrompt(bool),
     /// Select the given item and quit.
     Select,
+    /// Accept the contents of the prompt instead of an item, and quit.
+    AcceptQuery,
+    /// Select the given item, if any, and quit, recording the key which was pressed.
+    Expect(String),
     /// Quit without selecting an item.
     Quit,
+    /// Remove all of the items and add them again.
+    Reload,
+}
+
+/// The number of columns reserved for icons at the left edge of the match list.
+fn icon_column_width(config: &PickerConfig) -> u16 {
+    if config.icons {
+        2
+    } else {
+        0
+    }
+}
+
+/// The maximum number of rows which can be occupied by a single item.
+fn max_item_size(config: &PickerConfig) -> usize {
+    config
+        .max_item_height
+        .map_or(usize::MAX, |height| height.get() as usize)
+}
+
+/// The trailer of an item, along with the length which remains to draw the item.
+///
+/// The trailer takes at most half of the space which remains after the single column separating
+/// it from the item, so that the item always has at least one column.
+fn item_trailer<'a, T, R: Render<T>>(
+    render: &R,
+    item: &'a T,
+    width_policy: WidthPolicy,
+    max_draw_length: u16,
+) -> (Option<Cow<'a, str>>, u16) {
+    let mut trailer = render.trailer(item);
+    if let Some(text) = trailer.as_mut() {
+        if text.contains(char::is_control) {
+            normalize_query_string(text.to_mut());
+        }
+    }
+    let capacity = (max_draw_length.saturating_sub(1) / 2) as usize;
+    let trailer = trailer
+        .map(|text| match text {
+            Cow::Borrowed(text) => Cow::Borrowed(width_policy.truncate(text, capacity)),
+            Cow::Owned(text) => Cow::Owned(width_policy.truncate(&text, capacity).to_owned()),
+        })
+        .filter(|text| !text.is_empty());
+
+    let draw_length = match &trailer {
+        Some(text) => max_draw_length - width_policy.str_width(text) as u16 - 1,
+        None => max_draw_length,
+    };
+    (trailer, draw_length)
 }
 
 /// The dimension parameters of various items in the screen.
@@ -63,6 +209,17 @@ struct Dimensions {
     width: u16,
     /// The height of the screen, including the prompt.
     height: u16,
+    /// The index of the first row of the screen in the terminal, which is only non-zero when
+    /// rendering inline.
+    top: u16,
+    /// The number of rows reserved at the top of the screen.
+    header_height: u16,
+    /// The number of columns reserved at the left of the match list.
+    icon_width: u16,
+    /// The number of columns reserved at the right of the match list.
+    scrollbar_width: u16,
+    /// The width of the prompt prefix.
+    prompt_prefix_width: u16,
     /// The padding at the bottom.
     scroll_padding_bottom: u16,
     /// The padding at the top.
@@ -70,49 +227,104 @@ struct Dimensions {
 }
 
 impl Dimensions {
-    /// Initialize based on screen dimensions.
-    pub fn from_screen(config: &PickerConfig, width: u16, height: u16) -> Self {
-        let scroll_padding = config.scroll_padding.min(height.saturating_sub(3) / 2);
-        Self {
+    /// Initialize based on screen dimensions. The `top` row is only used when rendering inline,
+    /// and is clamped so that the picker fits on the screen.
+    pub fn from_screen(config: &PickerConfig, width: u16, screen_height: u16, top: u16) -> Self {
+        let height = config.inline_height.map_or(screen_height, |inline_height| {
+            inline_height.min(screen_height)
+        });
+        let mut dimensions = Self {
             width,
             height,
-            scroll_padding_bottom: scroll_padding,
-            scroll_padding_top: scroll_padding,
-        }
+            top: top.min(screen_height - height),
+            header_height: u16::from(config.overflow_indicators),
+            icon_width: icon_column_width(config),
+            scrollbar_width: u16::from(config.scrollbar.is_some()),
+            prompt_prefix_width: config
+                .width_policy
+                .str_width(&config.prompt_prefix)
+                .try_into()
+                .unwrap_or(u16::MAX),
+            scroll_padding_bottom: 0,
+            scroll_padding_top: 0,
+        };
+        let scroll_padding = config
+            .scroll_padding
+            .min(dimensions.max_draw_height().saturating_sub(1) / 2);
+        dimensions.scroll_padding_bottom = scroll_padding;
+        dimensions.scroll_padding_top = scroll_padding;
+        dimensions
     }
 
     pub fn move_to_screen_index(&self, index: u16) -> MoveTo {
-        MoveTo(0, self.max_draw_height() - 1 - index)
-    }
-
-    pub fn move_to_end_of_line(&self) -> MoveToColumn {
-        MoveToColumn(self.width - 1)
+        MoveTo(
+            0,
+            self.top + self.header_height + self.max_draw_height() - 1 - index,
+        )
     }
 
     /// The [`MoveTo`] command for setting the cursor at the bottom left corner of the match
     /// printing area.
     pub fn move_to_results_start(&self) -> MoveTo {
-        MoveTo(0, self.max_draw_height())
+        MoveTo(0, self.top + self.header_height + self.max_draw_height())
+    }
+
+    /// The [`MoveTo`] command for setting the cursor at the scrollbar in the row with the given
+    /// screen index.
+    pub fn move_to_scrollbar(&self, index: u16) -> MoveTo {
+        let MoveTo(_, row) = self.move_to_screen_index(index);
+        MoveTo(self.width.saturating_sub(1), row)
+    }
+
+    /// The screen index of the match drawn in the given terminal row, if any.
+    pub fn screen_index_at_row(&self, row: u16) -> Option<u16> {
+        let first_row = self.top + self.header_height;
+        if row >= first_row && row < first_row + self.max_draw_height() {
+            Some(first_row + self.max_draw_height() - 1 - row)
+        } else {
+            None
+        }
+    }
+
+    /// The [`MoveTo`] command for setting the cursor at the start of the first row of the screen.
+    pub fn move_to_top(&self) -> MoveTo {
+        MoveTo(0, self.top)
+    }
+
+    /// The [`MoveTo`] command for setting the cursor at the start of the last row of the screen.
+    pub fn move_to_bottom(&self) -> MoveTo {
+        MoveTo(0, self.top + self.height.saturating_sub(1))
+    }
+
+    /// Whether or not the screen is smaller than the minimum size in either dimension.
+    pub fn is_too_small(&self, min_size: Option<(u16, u16)>) -> bool {
+        min_size.is_some_and(|(width, height)| self.width < width || self.height < height)
+    }
+
+    /// Whether or not there is enough space to draw the header.
+    pub fn has_header(&self) -> bool {
+        self.header_height != 0 && self.height >= self.header_height + 2
     }
 
     /// The maximum width of the prompt string display window.
     pub fn max_prompt_width(&self) -> u16 {
-        self.width.saturating_sub(2)
+        self.width.saturating_sub(self.prompt_prefix_width)
     }
 
     /// The maximum number of matches which can be drawn to the screen.
     pub fn max_draw_height(&self) -> u16 {
-        self.height.saturating_sub(2)
+        self.height.saturating_sub(2 + self.header_height)
     }
 
     /// The maximum length on which a match can be drawn.
     pub fn max_draw_length(&self) -> u16 {
-        self.width.saturating_sub(2)
+        self.width
+            .saturating_sub(2 + self.icon_width + self.scrollbar_width)
     }
 
     /// The y index of the prompt string.
     fn prompt_y(&self) -> u16 {
-        self.height.saturating_sub(1)
+        self.top + self.height.saturating_sub(1)
     }
 
     /// The command to move to the start of the prompt rendering region.
@@ -122,7 +334,10 @@ impl Dimensions {
 
     /// The command to move to the cursor position.
     pub fn move_to_cursor(&self, view_position: u16) -> MoveTo {
-        MoveTo(view_position + 2, self.prompt_y())
+        MoveTo(
+            (view_position + self.prompt_prefix_width).min(self.width.saturating_sub(1)),
+            self.prompt_y(),
+        )
     }
 }
 
@@ -136,6 +351,37 @@ pub struct PickerConfig {
     pub highlight_padding: u16,
     pub scroll_padding: u16,
     pub prompt_padding: u16,
+    pub overflow_indicators: bool,
+    pub restore_on_signal: bool,
+    pub tty_fallback: bool,
+    pub legacy_console: bool,
+    pub clear_on_exit: bool,
+    pub width_policy: WidthPolicy,
+    pub inline_height: Option<u16>,
+    pub min_size: Option<(u16, u16)>,
+    pub keybindings: KeyBindings,
+    pub mouse: bool,
+    pub theme: Theme,
+    pub scrollbar: Option<Scrollbar>,
+    pub prompt_prefix: String,
+    pub placeholder: Option<String>,
+    pub wrap: bool,
+    pub exit_if_empty: bool,
+    pub status_line: Option<StatusLine>,
+    pub on_frame: Option<FrameHook>,
+    pub loading_indicator: bool,
+    pub sticky_selection: bool,
+    pub cycle: bool,
+    pub timeout: Option<Duration>,
+    pub no_match_text: Option<String>,
+    pub icons: bool,
+    pub max_item_height: Option<NonZero<u16>>,
+    pub sanitize_rendered: bool,
+    pub truncate: Truncate,
+    pub color_mode: ColorMode,
+    pub print_on_exit: bool,
+    pub print_delimiter: char,
+    pub tick_timeout: Duration,
 }
 
 impl Default for PickerConfig {
@@ -147,6 +393,37 @@ impl Default for PickerConfig {
             highlight_padding: 3,
             scroll_padding: 3,
             prompt_padding: 3,
+            overflow_indicators: false,
+            restore_on_signal: false,
+            tty_fallback: false,
+            legacy_console: false,
+            clear_on_exit: true,
+            width_policy: WidthPolicy::Standard,
+            inline_height: None,
+            min_size: None,
+            keybindings: KeyBindings::default(),
+            mouse: false,
+            theme: Theme::default(),
+            scrollbar: None,
+            prompt_prefix: "> ".to_owned(),
+            placeholder: None,
+            wrap: false,
+            exit_if_empty: false,
+            status_line: None,
+            on_frame: None,
+            loading_indicator: false,
+            sticky_selection: false,
+            cycle: false,
+            timeout: None,
+            no_match_text: None,
+            icons: false,
+            max_item_height: None,
+            sanitize_rendered: false,
+            truncate: Truncate::End,
+            color_mode: ColorMode::Auto,
+            print_on_exit: false,
+            print_delimiter: '\n',
+            tick_timeout: Duration::from_millis(10),
         }
     }
 }
@@ -158,6 +435,14 @@ pub struct CompositorBuffer {
     lines: Vec<Range<usize>>,
     /// Indices generated from a match.
     indices: Vec<u32>,
+    /// The indices of the items drawn in the previous frame, keyed by the address of the item.
+    cached_indices: HashMap<usize, Vec<u32>>,
+    /// The indices of the items drawn in the current frame.
+    frame_indices: HashMap<usize, Vec<u32>>,
+    /// The pattern used to compute the cached indices.
+    cached_atoms: Vec<Atom>,
+    /// The generation of the items for which the indices were cached.
+    cached_generation: u64,
 }
 
 impl CompositorBuffer {
@@ -166,10 +451,60 @@ impl CompositorBuffer {
             spans: Vec::with_capacity(16),
             lines: Vec::with_capacity(4),
             indices: Vec::with_capacity(16),
+            cached_indices: HashMap::new(),
+            frame_indices: HashMap::new(),
+            cached_atoms: Vec::new(),
+            cached_generation: 0,
+        }
+    }
+
+    /// Prepare the indices cache to draw a new frame with the given pattern and generation of
+    /// items. The indices of the items drawn in the previous frame are kept if the pattern and
+    /// the generation are unchanged, and the remaining indices are discarded. Returns whether or
+    /// not the pattern and the generation are unchanged.
+    ///
+    /// The indices must be discarded when the generation changes since the items are keyed by
+    /// address, and the addresses of removed items may be reused by new items.
+    fn start_frame(&mut self, pattern: &Pattern, generation: u64) -> bool {
+        let unchanged = pattern.atoms == self.cached_atoms && generation == self.cached_generation;
+        if unchanged {
+            mem::swap(&mut self.cached_indices, &mut self.frame_indices);
+        } else {
+            self.cached_indices.clear();
+            self.cached_atoms.clone_from(&pattern.atoms);
+            self.cached_generation = generation;
         }
+        self.frame_indices.clear();
+        unchanged
     }
 }
 
+/// The state in which an item was drawn to the screen. An item which is drawn in the same state
+/// as in the previous frame is not drawn again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DrawnItem {
+    /// The address of the item.
+    address: usize,
+    /// The number of match rows up to and including the item.
+    row: u16,
+    /// The number of rows occupied by the item.
+    height: u16,
+    /// Whether or not the item is selected.
+    selected: bool,
+    /// Whether or not the item is above the selection, in which case the last lines are kept if
+    /// the item does not fit on the screen.
+    above: bool,
+}
+
+/// The largest horizontal scroll of an item if it was drawn in the same state in the previous
+/// frame, in which case it does not need to be drawn again.
+fn previously_drawn(drawn: &[(DrawnItem, usize)], state: &DrawnItem) -> Option<usize> {
+    drawn
+        .iter()
+        .find(|(previous, _)| previous == state)
+        .map(|(_, item_scroll)| *item_scroll)
+}
+
 /// The struct which draws the content to the screen.
 #[derive(Debug)]
 pub struct Compositor<'a> {
@@ -189,13 +524,47 @@ pub struct Compositor<'a> {
     config: &'a PickerConfig,
     /// Stateful representation of the current screen layout.
     layout: Layout,
+    /// The number of matches which are not visible below the screen.
+    hidden_below: u32,
+    /// The number of matches which are not visible above the screen.
+    hidden_above: u32,
+    /// The index of the match drawn at each screen index.
+    match_rows: Vec<u32>,
+    /// The time and the terminal row of the previous click, used to detect double clicks.
+    last_click: Option<(Instant, u16)>,
+    /// The current case matching mode.
+    case_matching: CaseMatching,
+    /// Whether or not query atoms are matched as substrings by default.
+    exact: bool,
+    /// The time at which the compositor was created, used to animate the loading indicator.
+    created: Instant,
+    /// Whether or not more items may be added to the picker.
+    loading: bool,
+    /// Whether or not the matcher is still processing items.
+    matching: bool,
+    /// The current frame of the loading indicator, or `None` if the items have finished loading
+    /// and matching.
+    spinner_frame: Option<usize>,
+    /// The address of the selected item before the matches changed, used to find the item again
+    /// when the selection is sticky.
+    tracked: Option<usize>,
+    /// The generation of the items in the most recent snapshot.
+    generation: u64,
+    /// The time of the most recent keyboard or mouse input.
+    last_input: Instant,
+    /// The number of columns by which the matches are scrolled to the right.
+    horizontal_scroll: usize,
+    /// The items drawn in the previous frame, along with the largest horizontal scroll which has
+    /// an effect on the item.
+    drawn: Vec<(DrawnItem, usize)>,
 }
 
 impl<'a> Compositor<'a> {
-    /// The initial state.
-    pub fn new(screen: (u16, u16), config: &'a PickerConfig) -> Self {
-        let dimensions = Dimensions::from_screen(config, screen.0, screen.1);
-        let prompt = EditableString::new(dimensions.max_prompt_width(), config.prompt_padding);
+    /// The initial state. The `top` row is only used when rendering inline.
+    pub fn new(screen: (u16, u16), top: u16, config: &'a PickerConfig) -> Self {
+        let dimensions = Dimensions::from_screen(config, screen.0, screen.1, top);
+        let mut prompt = EditableString::new(dimensions.max_prompt_width(), config.prompt_padding);
+        prompt.set_width_policy(config.width_policy);
 
         Self {
             dimensions,
@@ -206,6 +575,32 @@ impl<'a> Compositor<'a> {
             needs_redraw: true,
             config,
             layout: Layout::default(),
+            hidden_below: 0,
+            hidden_above: 0,
+            match_rows: Vec::new(),
+            last_click: None,
+            case_matching: config.case_matching,
+            exact: false,
+            created: Instant::now(),
+            loading: false,
+            matching: false,
+            spinner_frame: None,
+            tracked: None,
+            generation: 0,
+            last_input: Instant::now(),
+            horizontal_scroll: 0,
+            drawn: Vec::new(),
+        }
+    }
+
+    /// The state of the picker, as shown in the status line.
+    pub fn status(&self) -> Status {
+        Status {
+            matched: self.matched_item_count,
+            total: self.item_count,
+            selection: self.selection(),
+            loading: self.loading,
+            matching: self.matching,
         }
     }
 
@@ -219,43 +614,149 @@ impl<'a> Compositor<'a> {
         }
     }
 
-    /// Increment the current item selection without exceeding the provided bound.
+    /// Increment the current item selection without exceeding the provided bound, or wrap around
+    /// to the first item if cycling is enabled.
     fn incr_selection(&mut self) {
         if self.selection < self.matched_item_count.saturating_sub(1) as usize {
             self.needs_redraw = true;
             self.selection += 1;
+        } else if self.config.cycle && self.selection > 0 {
+            self.needs_redraw = true;
+            self.selection = 0;
         }
     }
 
-    /// Decrement the current item selection.
+    /// Decrement the current item selection, or wrap around to the last item if cycling is
+    /// enabled.
     fn decr_selection(&mut self) {
         if let Some(new) = self.selection.checked_sub(1) {
             self.needs_redraw = true;
             self.selection = new;
+        } else if self.config.cycle && self.matched_item_count > 1 {
+            self.needs_redraw = true;
+            self.selection = self.matched_item_count as usize - 1;
+        }
+    }
+
+    /// Move the selection to the match with the given index.
+    fn set_selection(&mut self, index: usize) {
+        if self.selection != index {
+            self.needs_redraw = true;
+            self.selection = index;
+        }
+    }
+
+    /// Move the selection to the match drawn in the given terminal row. Returns `true` if the
+    /// click was a double click, in which case the item should be selected.
+    ///
+    /// Since moving the selection may scroll the screen, a double click is detected using the
+    /// terminal row rather than the match, and selects the match under the first click.
+    fn click(&mut self, row: u16) -> bool {
+        // the matches are not drawn
+        if self.dimensions.is_too_small(self.config.min_size) {
+            return false;
+        }
+
+        let Some(&index) = self
+            .dimensions
+            .screen_index_at_row(row)
+            .and_then(|screen_index| self.match_rows.get(screen_index as usize))
+        else {
+            return false;
+        };
+
+        let now = Instant::now();
+        if let Some((previous, previous_row)) = self.last_click.take() {
+            if previous_row == row && now.duration_since(previous) < DOUBLE_CLICK_INTERVAL {
+                return true;
+            }
+        }
+        self.last_click = Some((now, row));
+
+        self.set_selection(index as usize);
+        false
+    }
+
+    /// Remember the currently selected item, if the selection is sticky. This must be called
+    /// before the snapshot is updated.
+    ///
+    /// Items are identified by address, since they are never moved by the matcher engine.
+    pub fn track<T: Send + Sync + 'static>(&mut self, snapshot: &nucleo::Snapshot<T>) {
+        if self.config.sticky_selection {
+            self.tracked = self
+                .selection()
+                .and_then(|index| snapshot.get_matched_item(index))
+                .map(|item| ptr::from_ref(item.data) as usize);
         }
     }
 
-    /// Update the draw count from a snapshot.
+    /// Update the draw count from a snapshot and the status of the matcher, whether or not more
+    /// items are being loaded, and the generation of the items, which changes whenever the items
+    /// are removed.
     pub fn update<T: Send + Sync + 'static>(
         &mut self,
-        changed: bool,
+        status: nucleo::Status,
         snapshot: &nucleo::Snapshot<T>,
+        loading: bool,
+        generation: u64,
     ) {
-        if changed {
+        if generation != self.generation {
+            // the items were removed, and new items may be drawn at the addresses of the removed
+            // items, so every item must be drawn again and the selected item cannot be found
+            self.generation = generation;
+            self.drawn.clear();
+            self.tracked = None;
+        }
+
+        if loading != self.loading || status.running != self.matching {
+            self.loading = loading;
+            self.matching = status.running;
+            self.needs_redraw |=
+                self.config.status_line.is_some() || self.config.no_match_text.is_some();
+            #[cfg(feature = "tracing")]
+            tracing::trace!(loading, matching = status.running, "status changed");
+        }
+
+        let spinner_frame = (loading || status.running).then(|| {
+            (self.created.elapsed().as_millis() / SPINNER_INTERVAL.as_millis()) as usize
+                % SPINNER.len()
+        });
+        if spinner_frame != self.spinner_frame {
+            self.spinner_frame = spinner_frame;
+            self.needs_redraw |= self.config.loading_indicator;
+        }
+
+        if status.changed {
+            #[cfg(feature = "tracing")]
+            tracing::trace!("matches changed");
             self.needs_redraw = true;
             self.item_count = snapshot.item_count();
             self.matched_item_count = snapshot.matched_item_count();
-            self.selection = self
-                .selection
-                .min(self.matched_item_count.saturating_sub(1) as usize);
+
+            let tracked = self.tracked.take().and_then(|tracked| {
+                find_tracked(
+                    snapshot,
+                    tracked,
+                    self.selection as u32,
+                    u32::from(self.dimensions.max_draw_height()),
+                )
+            });
+            self.selection = match tracked {
+                Some(index) => index as usize,
+                None => self
+                    .selection
+                    .min(self.matched_item_count.saturating_sub(1) as usize),
+            };
         }
     }
 
     /// Perform the given edit action.
     #[inline]
     fn edit_prompt(&mut self, st: Edit) -> bool {
+        let paste_truncated = self.prompt.paste_truncated();
         let changed = self.prompt.edit(st);
-        self.needs_redraw |= changed;
+        // the warning for a truncated paste is shown until the next edit
+        self.needs_redraw |= changed || paste_truncated != self.prompt.paste_truncated();
         changed
     }
 
@@ -265,6 +766,33 @@ impl<'a> Compositor<'a> {
         self.needs_redraw = true;
     }
 
+    /// The current case matching mode, which can be toggled interactively.
+    pub fn case_matching(&self) -> CaseMatching {
+        self.case_matching
+    }
+
+    /// Whether or not an update to the pattern can use the append optimization; this is never the
+    /// case in exact mode since the query is converted before parsing.
+    fn supports_append(&self) -> bool {
+        !self.exact
+    }
+
+    /// The text which should be parsed as the matcher pattern, which is the prompt contents,
+    /// possibly converted for exact matching.
+    pub fn pattern_text(&self) -> Cow<'_, str> {
+        if self.exact {
+            Cow::Owned(exact_query(self.prompt.contents()))
+        } else {
+            Cow::Borrowed(self.prompt.contents())
+        }
+    }
+
+    /// The time elapsed since the most recent keyboard or mouse input, or since the compositor
+    /// was created if there was no input.
+    pub fn idle_time(&self) -> Duration {
+        self.last_input.elapsed()
+    }
+
     /// The current contents of the prompt.
     pub fn prompt_contents(&self) -> &str {
         self.prompt.contents()
@@ -274,13 +802,23 @@ impl<'a> Compositor<'a> {
     pub fn handle(&mut self) -> Result<EventSummary, io::Error> {
         let mut update_prompt = false;
         let mut append = true;
+        #[cfg(feature = "tracing")]
+        let mut processed = 0_usize;
 
         while poll(Duration::from_millis(5))? {
-            if let Some(event) = convert(read()?) {
+            if let Some(event) = convert(read()?, &self.config.keybindings) {
+                #[cfg(feature = "tracing")]
+                {
+                    tracing::trace!(?event, "event");
+                    processed += 1;
+                }
+
+                if !matches!(event, Event::Resize(..)) {
+                    self.last_input = Instant::now();
+                }
+
                 match event {
-                    Event::Abort => {
-                        return Err(io::Error::new(io::ErrorKind::Other, "keyboard interrupt"))
-                    }
+                    Event::Abort => return Err(PickError::KeyboardInterrupt.into()),
                     Event::MoveToStart => {
                         self.edit_prompt(Edit::ToStart);
                     }
@@ -292,8 +830,34 @@ impl<'a> Compositor<'a> {
                         update_prompt |= self.edit_prompt(Edit::Insert(ch));
                     }
                     Event::Select => return Ok(EventSummary::Select),
+                    Event::SelectOrQuery => {
+                        return Ok(if self.selection().is_some() {
+                            EventSummary::Select
+                        } else {
+                            EventSummary::AcceptQuery
+                        });
+                    }
+                    Event::AcceptQuery => return Ok(EventSummary::AcceptQuery),
+                    Event::Expect(key) => return Ok(EventSummary::Expect(key)),
                     Event::MoveUp => self.incr_selection(),
+                    Event::MoveToFirst => self.set_selection(0),
+                    Event::MoveToLast => {
+                        self.set_selection(self.matched_item_count.saturating_sub(1) as usize);
+                    }
                     Event::MoveDown => self.decr_selection(),
+                    Event::ScrollLeft(columns) => {
+                        if self.horizontal_scroll > 0 {
+                            self.horizontal_scroll =
+                                self.horizontal_scroll.saturating_sub(columns as usize);
+                            self.drawn.clear();
+                            self.needs_redraw = true;
+                        }
+                    }
+                    Event::ScrollRight(columns) => {
+                        self.horizontal_scroll += columns as usize;
+                        self.drawn.clear();
+                        self.needs_redraw = true;
+                    }
                     Event::MoveLeft => {
                         self.edit_prompt(Edit::Left);
                     }
@@ -336,12 +900,47 @@ impl<'a> Compositor<'a> {
                             append = false;
                         }
                     }
+                    Event::Yank => {
+                        append &= self.prompt.is_appending();
+                        update_prompt |= self.edit_prompt(Edit::Yank);
+                    }
+                    Event::YankPop => {
+                        if self.edit_prompt(Edit::YankPop) {
+                            update_prompt = true;
+                            append = false;
+                        }
+                    }
+                    Event::Undo => {
+                        if self.edit_prompt(Edit::Undo) {
+                            update_prompt = true;
+                            append = false;
+                        }
+                    }
+                    Event::Redo => {
+                        if self.edit_prompt(Edit::Redo) {
+                            update_prompt = true;
+                            append = false;
+                        }
+                    }
                     Event::Quit => return Ok(EventSummary::Quit),
                     Event::QuitIfEmpty => {
                         if self.prompt.is_empty() {
                             return Ok(EventSummary::Quit);
                         }
                     }
+                    Event::ToggleCaseMatching => {
+                        self.case_matching = next_case_matching(self.case_matching);
+                        self.needs_redraw = true;
+                        update_prompt = true;
+                        append = false;
+                    }
+                    Event::ToggleExact => {
+                        self.exact = !self.exact;
+                        self.needs_redraw = true;
+                        update_prompt = true;
+                        append = false;
+                    }
+                    Event::Reload => return Ok(EventSummary::Reload),
                     Event::Resize(width, height) => {
                         self.resize(width, height);
                     }
@@ -349,17 +948,29 @@ impl<'a> Compositor<'a> {
                         append &= self.prompt.is_appending();
                         update_prompt |= self.edit_prompt(Edit::Paste(contents));
                     }
+                    Event::Click(row) => {
+                        if self.click(row) {
+                            return Ok(EventSummary::Select);
+                        }
+                    }
                 }
             }
         }
+
+        #[cfg(feature = "tracing")]
+        if processed > 0 {
+            tracing::debug!(processed, update_prompt, "events processed");
+        }
+
         Ok(if update_prompt {
-            EventSummary::UpdatePrompt(append)
+            EventSummary::UpdatePrompt(append && self.supports_append())
         } else {
             EventSummary::Continue
         })
     }
 
-    /// The inner `match draw` implementation.
+    /// The inner `match draw` implementation. Returns the largest horizontal scroll which has an
+    /// effect on the item.
     #[inline]
     #[allow(clippy::too_many_arguments)]
     fn draw_single_match<
@@ -372,42 +983,181 @@ impl<'a> Compositor<'a> {
         stderr: &mut W,
         buffer: &mut CompositorBuffer,
         max_draw_length: u16,
+        scroll: usize,
         config: &PickerConfig,
         item: &nucleo::Item<'_, T>,
         snapshot: &nucleo::Snapshot<T>,
         matcher: &mut nucleo::Matcher,
         height: u16,
         render: &R,
-    ) -> Result<(), io::Error> {
-        // generate the indices
+    ) -> Result<usize, io::Error> {
+        // generate the indices, reusing the indices from the previous frame if possible since
+        // they are expensive to compute for long or non-ASCII items
         if config.highlight {
+            let key = ptr::from_ref(item.data) as usize;
             buffer.indices.clear();
-            snapshot.pattern().column_pattern(0).indices(
-                item.matcher_columns[0].slice(..),
-                matcher,
-                &mut buffer.indices,
-            );
-            buffer.indices.sort_unstable();
-            buffer.indices.dedup();
+            if let Some(indices) = buffer.cached_indices.remove(&key) {
+                buffer.indices.extend_from_slice(&indices);
+                buffer.frame_indices.insert(key, indices);
+            } else {
+                snapshot.pattern().column_pattern(0).indices(
+                    item.matcher_columns[0].slice(..),
+                    matcher,
+                    &mut buffer.indices,
+                );
+                buffer.indices.sort_unstable();
+                buffer.indices.dedup();
+
+                // remove the indices which correspond to keywords, since they are not rendered
+                let rendered_len = rendered_column(&item.matcher_columns[0]).len() as u32;
+                buffer.indices.retain(|idx| *idx < rendered_len);
+                buffer.frame_indices.insert(key, buffer.indices.clone());
+            }
         }
 
-        match RenderedItem::new(item, render) {
-            RenderedItem::Ascii(s) => Spanned::<'_, AsciiProcessor>::new(
-                &buffer.indices,
+        let style = LineStyle {
+            selected: SELECTED,
+            item: render.style(item.data),
+            theme: &config.theme,
+            icon_column: config.icons,
+        };
+
+        // the item must be drawn with the same length as in `WrappedSnapshot`, so that wrapped
+        // items occupy the number of rows reserved by the layout
+        let (trailer, max_draw_length) =
+            item_trailer(render, item.data, config.width_policy, max_draw_length);
+
+        let max_scroll = match RenderedItem::new(item, render, config.sanitize_rendered) {
+            RenderedItem::Ascii(s) => Self::draw_rendered::<_, L, W>(
+                AsciiProcessor,
+                stderr,
+                buffer,
                 s,
+                max_draw_length,
+                scroll,
+                config,
+                height,
+                style,
+            ),
+            RenderedItem::Unicode(r) => with_processor!(config.width_policy, |processor| {
+                Self::draw_rendered::<_, L, W>(
+                    processor,
+                    stderr,
+                    buffer,
+                    r.as_ref(),
+                    max_draw_length,
+                    scroll,
+                    config,
+                    height,
+                    style,
+                )
+            }),
+        }?;
+
+        // indicate that lines were omitted after the last line of a clamped item
+        let max_size = max_item_size(config);
+        if buffer.lines.len() > max_size && L::from_offset(height).keeps_last(max_size) {
+            stderr
+                .queue(MoveToPreviousLine(1))?
+                .queue(MoveToColumn(icon_column_width(config) + 1))?;
+            if SELECTED {
+                stderr.queue(SetStyle(config.theme.selection))?;
+            }
+            stderr
+                .queue(PrintStyledContent(StyledContent::new(
+                    config.theme.trailer,
+                    ELLIPSIS,
+                )))?
+                .queue(SetAttribute(Attribute::Reset))?
+                .queue(MoveToNextLine(1))?;
+        }
+
+        let icon = if config.icons {
+            render
+                .icon(item.data)
+                .filter(|ch| matches!(config.width_policy.char_width(*ch), Some(1 | 2)))
+        } else {
+            None
+        };
+
+        if trailer.is_some() || icon.is_some() {
+            // drawing the item moves the cursor to the line after the item, so we return to the
+            // first line of the item to draw the icon and the trailer
+            stderr.queue(MoveToPreviousLine(height))?;
+
+            if let Some(ch) = icon {
+                stderr.queue(Print(ch))?;
+            }
+
+            if let Some(text) = trailer {
+                stderr.queue(MoveToColumn(
+                    icon_column_width(config) + 2 + max_draw_length + 1,
+                ))?;
+                if SELECTED {
+                    stderr.queue(SetStyle(config.theme.selection))?;
+                }
+                stderr
+                    .queue(PrintStyledContent(StyledContent::new(
+                        config.theme.trailer,
+                        text,
+                    )))?
+                    .queue(SetAttribute(Attribute::Reset))?;
+            }
+        }
+
+        Ok(max_scroll)
+    }
+
+    /// Draw a rendered item using the match indices stored in the buffer, either truncating or
+    /// wrapping the lines which do not fit on the screen.
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn draw_rendered<P: Processor, L: KeepLines, W: Write>(
+        processor: P,
+        stderr: &mut W,
+        buffer: &mut CompositorBuffer,
+        rendered: &str,
+        max_draw_length: u16,
+        scroll: usize,
+        config: &PickerConfig,
+        height: u16,
+        style: LineStyle<'_>,
+    ) -> Result<usize, io::Error> {
+        if config.wrap {
+            Spanned::new_wrapped(
+                processor,
+                &buffer.indices,
+                rendered,
                 &mut buffer.spans,
                 &mut buffer.lines,
-                L::from_offset(height),
+                Clamp {
+                    inner: L::from_offset(height),
+                    max: max_item_size(config),
+                },
+                max_draw_length.max(1),
             )
-            .queue_print(stderr, SELECTED, max_draw_length, config.highlight_padding),
-            RenderedItem::Unicode(r) => Spanned::<'_, UnicodeProcessor>::new(
+            .queue_print_all(stderr, style)?;
+            Ok(0)
+        } else {
+            Spanned::new(
+                processor,
                 &buffer.indices,
-                r.as_ref(),
+                rendered,
                 &mut buffer.spans,
                 &mut buffer.lines,
-                L::from_offset(height),
+                Clamp {
+                    inner: L::from_offset(height),
+                    max: max_item_size(config),
+                },
+            )
+            .queue_print(
+                stderr,
+                style,
+                max_draw_length,
+                config.highlight_padding,
+                config.truncate,
+                scroll,
             )
-            .queue_print(stderr, SELECTED, max_draw_length, config.highlight_padding),
         }
     }
 
@@ -422,22 +1172,82 @@ impl<'a> Compositor<'a> {
     ) -> Result<(), io::Error> {
         // draw the matches
         if snapshot.matched_item_count() == 0 {
+            self.hidden_below = 0;
+            self.hidden_above = 0;
+            self.match_rows.clear();
+            self.drawn.clear();
+
             // erase the matches if there are no matched items
-            stderr
-                .queue(MoveToPreviousLine(1))?
-                .queue(self.dimensions.move_to_end_of_line())?
-                .queue(Clear(ClearType::FromCursorUp))?;
+            self.clear_matches_from(stderr, 0)?;
+
+            // only report that nothing matched once there are items and matching is complete
+            if snapshot.item_count() > 0 && !self.matching {
+                if let Some(no_match_text) = &self.config.no_match_text {
+                    self.draw_no_match_text(stderr, no_match_text)?;
+                }
+            }
         } else {
             // recompute the layout
-            let view = self.layout.recompute(
-                self.dimensions.max_draw_height(),
-                self.dimensions.scroll_padding_bottom,
-                self.dimensions.scroll_padding_top,
-                self.selection as u32,
-                snapshot,
+            let view = if self.config.wrap {
+                self.layout.recompute(
+                    self.dimensions.max_draw_height(),
+                    self.dimensions.scroll_padding_bottom,
+                    self.dimensions.scroll_padding_top,
+                    self.selection as u32,
+                    &Clamped {
+                        buffer: &WrappedSnapshot {
+                            snapshot,
+                            render,
+                            width: self.dimensions.max_draw_length().max(1),
+                            width_policy: self.config.width_policy,
+                            sanitize: self.config.sanitize_rendered,
+                        },
+                        max_size: max_item_size(self.config),
+                    },
+                )
+            } else {
+                self.layout.recompute(
+                    self.dimensions.max_draw_height(),
+                    self.dimensions.scroll_padding_bottom,
+                    self.dimensions.scroll_padding_top,
+                    self.selection as u32,
+                    &Clamped {
+                        buffer: snapshot,
+                        max_size: max_item_size(self.config),
+                    },
+                )
+            };
+
+            #[cfg(feature = "tracing")]
+            tracing::trace!(
+                below = view.below.len(),
+                above = view.above.len(),
+                selection = self.selection,
+                "layout recomputed"
             );
 
+            self.hidden_below = self.selection as u32 + 1 - view.below.len() as u32;
+            self.hidden_above =
+                snapshot.matched_item_count() - 1 - self.selection as u32 - view.above.len() as u32;
+
+            // record the match drawn at each screen index, in increasing order
+            self.match_rows.clear();
+            for (index, height) in (self.selection as u32 + 1 - view.below.len() as u32..)
+                .zip(view.below.iter().rev().chain(view.above))
+            {
+                self.match_rows.extend(repeat_n(index, *height as usize));
+            }
+
+            // the items must be drawn again if the highlighted characters may have changed
+            if !buffer.start_frame(snapshot.pattern().column_pattern(0), self.generation) {
+                #[cfg(feature = "tracing")]
+                tracing::trace!("pattern or items changed");
+                self.drawn.clear();
+            }
+
             let mut match_lines_rendered = 0;
+            let mut max_scroll = 0;
+            let mut drawn = Vec::with_capacity(self.drawn.len());
             let mut item_iter = snapshot.matched_items(
                 self.selection as u32 + 1 - view.below.len() as u32
                     ..=self.selection as u32 + view.above.len() as u32,
@@ -446,114 +1256,310 @@ impl<'a> Compositor<'a> {
             // render below the selection
             for height in view.below[1..].iter().rev() {
                 match_lines_rendered += height;
-                stderr.queue(
-                    self.dimensions
-                        .move_to_screen_index(match_lines_rendered - 1),
-                )?;
+                let item = item_iter.next().unwrap();
+                let state = DrawnItem {
+                    address: ptr::from_ref(item.data) as usize,
+                    row: match_lines_rendered,
+                    height: *height,
+                    selected: false,
+                    above: false,
+                };
 
-                Self::draw_single_match::<T, R, Head, W, false>(
-                    stderr,
-                    buffer,
-                    self.dimensions.max_draw_length(),
-                    self.config,
-                    &item_iter.next().unwrap(),
-                    snapshot,
-                    matcher,
-                    *height,
-                    render,
-                )?;
+                let item_scroll = match previously_drawn(&self.drawn, &state) {
+                    Some(item_scroll) => item_scroll,
+                    None => {
+                        stderr.queue(
+                            self.dimensions
+                                .move_to_screen_index(match_lines_rendered - 1),
+                        )?;
+
+                        Self::draw_single_match::<T, R, Head, W, false>(
+                            stderr,
+                            buffer,
+                            self.dimensions.max_draw_length(),
+                            self.horizontal_scroll,
+                            self.config,
+                            &item,
+                            snapshot,
+                            matcher,
+                            *height,
+                            render,
+                        )?
+                    }
+                };
+                max_scroll = max_scroll.max(item_scroll);
+                drawn.push((state, item_scroll));
             }
 
             // render the selection
             match_lines_rendered += view.below[0];
-            stderr.queue(
-                self.dimensions
-                    .move_to_screen_index(match_lines_rendered - 1),
-            )?;
+            let item = item_iter.next().unwrap();
+            let state = DrawnItem {
+                address: ptr::from_ref(item.data) as usize,
+                row: match_lines_rendered,
+                height: view.below[0],
+                selected: true,
+                above: false,
+            };
 
-            Self::draw_single_match::<T, R, Head, W, true>(
-                stderr,
-                buffer,
-                self.dimensions.max_draw_length(),
-                self.config,
-                &item_iter.next().unwrap(),
-                snapshot,
-                matcher,
-                view.below[0],
-                render,
-            )?;
+            let item_scroll = match previously_drawn(&self.drawn, &state) {
+                Some(item_scroll) => item_scroll,
+                None => {
+                    stderr.queue(
+                        self.dimensions
+                            .move_to_screen_index(match_lines_rendered - 1),
+                    )?;
+
+                    Self::draw_single_match::<T, R, Head, W, true>(
+                        stderr,
+                        buffer,
+                        self.dimensions.max_draw_length(),
+                        self.horizontal_scroll,
+                        self.config,
+                        &item,
+                        snapshot,
+                        matcher,
+                        view.below[0],
+                        render,
+                    )?
+                }
+            };
+            max_scroll = max_scroll.max(item_scroll);
+            drawn.push((state, item_scroll));
 
             // render above the selection
             for height in view.above {
                 match_lines_rendered += height;
-                stderr.queue(
-                    self.dimensions
-                        .move_to_screen_index(match_lines_rendered - 1),
-                )?;
+                let item = item_iter.next().unwrap();
+                let state = DrawnItem {
+                    address: ptr::from_ref(item.data) as usize,
+                    row: match_lines_rendered,
+                    height: *height,
+                    selected: false,
+                    above: true,
+                };
 
-                Self::draw_single_match::<T, R, Tail, W, false>(
-                    stderr,
-                    buffer,
-                    self.dimensions.max_draw_length(),
-                    self.config,
-                    &item_iter.next().unwrap(),
-                    snapshot,
-                    matcher,
-                    *height,
-                    render,
-                )?;
+                let item_scroll = match previously_drawn(&self.drawn, &state) {
+                    Some(item_scroll) => item_scroll,
+                    None => {
+                        stderr.queue(
+                            self.dimensions
+                                .move_to_screen_index(match_lines_rendered - 1),
+                        )?;
+
+                        Self::draw_single_match::<T, R, Tail, W, false>(
+                            stderr,
+                            buffer,
+                            self.dimensions.max_draw_length(),
+                            self.horizontal_scroll,
+                            self.config,
+                            &item,
+                            snapshot,
+                            matcher,
+                            *height,
+                            render,
+                        )?
+                    }
+                };
+                max_scroll = max_scroll.max(item_scroll);
+                drawn.push((state, item_scroll));
             }
+            self.drawn = drawn;
 
             // clear above matches if required
-            if match_lines_rendered + 1 < self.dimensions.max_draw_height() {
-                stderr
-                    .queue(self.dimensions.move_to_screen_index(match_lines_rendered))?
-                    .queue(self.dimensions.move_to_end_of_line())?
-                    .queue(Clear(ClearType::FromCursorUp))?;
-            }
+            self.clear_matches_from(stderr, match_lines_rendered)?;
+
+            // do not scroll further than required to see the end of the visible items
+            self.horizontal_scroll = self.horizontal_scroll.min(max_scroll);
         }
 
         Ok(())
     }
 
+    /// Draw the text displayed when there are no matches, centered in the match area. This must
+    /// be called after the match area is cleared.
+    fn draw_no_match_text<W: Write>(&self, stderr: &mut W, text: &str) -> Result<(), io::Error> {
+        let text = self.truncate_to_width(text, self.dimensions.width);
+        let width = self.config.width_policy.str_width(text) as u16;
+        let MoveTo(_, row) = self
+            .dimensions
+            .move_to_screen_index(self.dimensions.max_draw_height() / 2);
+
+        stderr
+            .queue(MoveTo((self.dimensions.width - width) / 2, row))?
+            .queue(PrintStyledContent(StyledContent::new(
+                self.config.theme.placeholder,
+                text,
+            )))?;
+        Ok(())
+    }
+
+    /// Clear the rows in the match drawing area, starting at the given screen index and moving
+    /// upwards. The rows are cleared individually so that the contents of the terminal outside
+    /// the picker are not modified when rendering inline.
+    #[inline]
+    fn clear_matches_from<W: Write>(&self, stderr: &mut W, start: u16) -> Result<(), io::Error> {
+        for index in start..self.dimensions.max_draw_height() {
+            stderr
+                .queue(self.dimensions.move_to_screen_index(index))?
+                .queue(Clear(ClearType::CurrentLine))?;
+        }
+        Ok(())
+    }
+
+    /// Draw the scrollbar on the right edge of the match list, if there are more matches than fit
+    /// on the screen. This must be called after the matches are drawn, since drawing a match clears
+    /// the remainder of the line.
+    fn draw_scrollbar<W: Write>(
+        &self,
+        stderr: &mut W,
+        scrollbar: Scrollbar,
+    ) -> Result<(), io::Error> {
+        let hidden = self.hidden_below + self.hidden_above;
+        if hidden == 0 {
+            return Ok(());
+        }
+
+        // the scrollbar only covers the rows containing matches; the size and position of the
+        // thumb is proportional to the number of matches, since the matches need not have the same
+        // height
+        let height = self.match_rows.len() as u64;
+        let total = u64::from(self.matched_item_count);
+        let visible = total - u64::from(hidden);
+        let thumb_height = (height * visible).div_ceil(total).clamp(1, height);
+        let thumb_start = ((height - thumb_height) * u64::from(self.hidden_below)
+            + u64::from(hidden) / 2)
+            / u64::from(hidden);
+
+        stderr.queue(SetStyle(self.config.theme.scrollbar))?;
+        for index in 0..height {
+            let glyph = if (thumb_start..thumb_start + thumb_height).contains(&index) {
+                scrollbar.thumb
+            } else {
+                scrollbar.track
+            };
+            stderr
+                .queue(self.dimensions.move_to_scrollbar(index as u16))?
+                .queue(Print(glyph))?;
+        }
+        stderr
+            .queue(SetAttribute(Attribute::Reset))?
+            .queue(ResetColor)?;
+        Ok(())
+    }
+
     /// Draw the prompt string
     fn draw_prompt<W: Write>(&self, stderr: &mut W) -> Result<(), io::Error> {
         let (contents, shift) = self.prompt.view();
 
         stderr
             .queue(self.dimensions.move_to_prompt())?
-            .queue(Print("> "))?;
+            .queue(PrintStyledContent(StyledContent::new(
+                self.config.theme.prompt,
+                self.truncate_to_width(&self.config.prompt_prefix, self.dimensions.width),
+            )))?;
 
         if shift != 0 {
             stderr.queue(MoveRight(shift))?;
         }
 
+        match &self.config.placeholder {
+            Some(placeholder) if self.prompt.is_empty() => {
+                stderr.queue(PrintStyledContent(StyledContent::new(
+                    self.config.theme.placeholder,
+                    self.truncate_to_width(placeholder, self.dimensions.max_prompt_width()),
+                )))?;
+            }
+            _ => {
+                stderr.queue(Print(contents))?;
+            }
+        }
+
         stderr
-            .queue(Print(contents))?
             .queue(Clear(ClearType::UntilNewLine))?
             .queue(self.dimensions.move_to_cursor(self.prompt.screen_offset()))?;
 
         Ok(())
     }
 
-    /// Draw the match counts to the terminal, e.g. `9/43`.
+    /// Truncate a string so that it fits in the given number of columns.
+    fn truncate_to_width<'s>(&self, text: &'s str, width: u16) -> &'s str {
+        self.config.width_policy.truncate(text, width as usize)
+    }
+
+    /// Draw the match counts to the terminal, e.g. `9/43`, or the custom status line if set.
     fn draw_match_counts<W: Write>(&mut self, writer: &mut W) -> Result<(), io::Error> {
         writer.queue(self.dimensions.move_to_results_start())?;
         writer
-            .queue(SetAttribute(Attribute::Italic))?
-            .queue(SetForegroundColor(Color::Green))?
             .queue(Print("  "))?
-            .queue(Print(self.matched_item_count))?
-            .queue(Print("/"))?
-            .queue(Print(self.item_count))?
+            .queue(SetStyle(self.config.theme.counter))?;
+        if let Some(status_line) = &self.config.status_line {
+            let mut text = status_line.text(&self.status());
+            normalize_query_string(&mut text);
+            writer.queue(Print(
+                self.truncate_to_width(&text, self.dimensions.width.saturating_sub(2)),
+            ))?;
+        } else {
+            writer
+                .queue(Print(self.matched_item_count))?
+                .queue(Print("/"))?
+                .queue(Print(self.item_count))?;
+        }
+        if self.config.loading_indicator {
+            if let Some(frame) = self.spinner_frame {
+                writer.queue(Print(' '))?.queue(Print(SPINNER[frame]))?;
+            }
+        }
+        if self.config.overflow_indicators && self.hidden_below > 0 {
+            writer
+                .queue(Print("  ↓ "))?
+                .queue(Print(self.hidden_below))?
+                .queue(Print(" more"))?;
+        }
+
+        // show the matching modes if they differ from the defaults
+        match self.case_matching {
+            CaseMatching::Smart => {}
+            CaseMatching::Ignore => {
+                writer.queue(Print("  [ignore case]"))?;
+            }
+            _ => {
+                writer.queue(Print("  [respect case]"))?;
+            }
+        }
+        if self.exact {
+            writer.queue(Print("  [exact]"))?;
+        }
+        if self.prompt.paste_truncated() {
+            writer.queue(Print("  [paste truncated]"))?;
+        }
+        writer
             .queue(SetAttribute(Attribute::Reset))?
             .queue(ResetColor)?
             .queue(Clear(ClearType::UntilNewLine))?;
         Ok(())
     }
 
-    /// Draw the terminal to the screen. This assumes that the draw count has been updated and the
-    /// selector index has been properly clamped, or this method will panic!
+    /// Draw the number of matches hidden above the screen, e.g. `↑ 120 more`.
+    fn draw_overflow_header<W: Write>(&mut self, writer: &mut W) -> Result<(), io::Error> {
+        writer.queue(self.dimensions.move_to_top())?;
+        if self.hidden_above > 0 {
+            writer
+                .queue(Print("  "))?
+                .queue(SetStyle(self.config.theme.counter))?
+                .queue(Print("↑ "))?
+                .queue(Print(self.hidden_above))?
+                .queue(Print(" more"))?
+                .queue(SetAttribute(Attribute::Reset))?
+                .queue(ResetColor)?;
+        }
+        writer.queue(Clear(ClearType::UntilNewLine))?;
+        Ok(())
+    }
+
+    /// Draw the terminal to the screen, returning whether or not anything was drawn. This assumes
+    /// that the draw count has been updated and the selector index has been properly clamped, or
+    /// this method will panic!
     pub fn draw<T: Send + Sync + 'static, R: Render<T>, W: Write>(
         &mut self,
         writer: &mut W,
@@ -561,40 +1567,346 @@ impl<'a> Compositor<'a> {
         render: &R,
         snapshot: &nucleo::Snapshot<T>,
         buffer: &mut CompositorBuffer,
-    ) -> Result<(), io::Error> {
-        if self.needs_redraw {
+    ) -> Result<bool, io::Error> {
+        let redraw = self.needs_redraw;
+        if redraw {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::trace_span!("draw").entered();
+
             // reset redraw state
             self.needs_redraw = false;
 
-            writer.execute(BeginSynchronizedUpdate)?;
+            // legacy consoles print synchronized update commands verbatim
+            if !self.config.legacy_console {
+                writer.execute(BeginSynchronizedUpdate)?;
+            }
 
-            // draw the match counts
-            self.draw_match_counts(writer)?;
+            if self.dimensions.is_too_small(self.config.min_size) {
+                self.draw_too_small(writer)?;
+            } else {
+                // draw matches if there is space; the height check is required otherwise the
+                // `recompute` function will panic
+                if self.dimensions.max_draw_height() != 0 {
+                    self.draw_matches(writer, matcher, render, snapshot, buffer)?;
 
-            // draw matches if there is space; the height check is required otherwise the
-            // `recompute` function will panic
-            if self.dimensions.max_draw_height() != 0 {
-                self.draw_matches(writer, matcher, render, snapshot, buffer)?;
-            }
+                    if let Some(scrollbar) = self.config.scrollbar {
+                        self.draw_scrollbar(writer, scrollbar)?;
+                    }
+                }
+
+                // draw the match counts
+                self.draw_match_counts(writer)?;
+
+                // draw the overflow indicator for matches above the screen
+                if self.dimensions.has_header() {
+                    self.draw_overflow_header(writer)?;
+                }
 
-            // render the prompt string
-            self.draw_prompt(writer)?;
+                // render the prompt string
+                self.draw_prompt(writer)?;
+            }
 
             // flush to terminal
             writer.flush()?;
-            writer.execute(EndSynchronizedUpdate)?;
+            if !self.config.legacy_console {
+                writer.execute(EndSynchronizedUpdate)?;
+            }
         };
 
+        Ok(redraw)
+    }
+
+    /// Clear the screen and draw a message with the minimum size, in place of the picker.
+    fn draw_too_small<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        for row in 0..self.dimensions.height {
+            writer
+                .queue(MoveTo(0, self.dimensions.top + row))?
+                .queue(Clear(ClearType::CurrentLine))?;
+        }
+
+        if let Some((width, height)) = self.config.min_size {
+            let message = format!("terminal too small (need {width}x{height})");
+            writer.queue(self.dimensions.move_to_top())?.queue(Print(
+                self.truncate_to_width(&message, self.dimensions.width),
+            ))?;
+        }
         Ok(())
     }
 
+    /// Clean up the rows used by an inline picker. If `clear` is true, the rows are erased and the
+    /// cursor is placed on the first row; otherwise, the cursor is placed on the line after the
+    /// picker.
+    pub fn finish_inline<W: Write>(&self, writer: &mut W, clear: bool) -> Result<(), io::Error> {
+        if clear {
+            writer
+                .queue(self.dimensions.move_to_top())?
+                .queue(Clear(ClearType::FromCursorDown))?;
+        } else {
+            writer
+                .queue(self.dimensions.move_to_bottom())?
+                .queue(Print("\r\n"))?;
+        }
+        writer.flush()
+    }
+
     /// Resize the terminal state on screen size change.
     fn resize(&mut self, width: u16, height: u16) {
         self.needs_redraw = true;
-        self.dimensions = Dimensions::from_screen(self.config, width, height);
+        // the contents of the screen may have been changed by the terminal
+        self.drawn.clear();
+        self.dimensions = Dimensions::from_screen(self.config, width, height, self.dimensions.top);
         self.prompt.resize(
             self.dimensions.max_prompt_width(),
             self.config.prompt_padding,
         );
     }
 }
+
+/// Find the index of the matched item with the given address.
+///
+/// The matches within `radius` of the previous selection are searched first, since adding items
+/// or editing the query usually only moves the item a short distance. Otherwise, at most
+/// [`STICKY_SEARCH_LIMIT`] matches are searched from the start of the list, so that the search
+/// does not scan every match when the item no longer matches.
+fn find_tracked<T: Send + Sync + 'static>(
+    snapshot: &nucleo::Snapshot<T>,
+    tracked: usize,
+    selection: u32,
+    radius: u32,
+) -> Option<u32> {
+    let count = snapshot.matched_item_count();
+    let is_tracked = |item: nucleo::Item<'_, T>| ptr::from_ref(item.data) as usize == tracked;
+
+    let start = selection.saturating_sub(radius).min(count);
+    let end = selection
+        .saturating_add(radius)
+        .saturating_add(1)
+        .min(count);
+    snapshot
+        .matched_items(start..end)
+        .position(is_tracked)
+        .map(|offset| start + offset as u32)
+        .or_else(|| {
+            snapshot
+                .matched_items(..count.min(STICKY_SEARCH_LIMIT))
+                .position(is_tracked)
+                .map(|index| index as u32)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{render::StrRenderer, Picker};
+
+    #[test]
+    fn test_find_tracked() {
+        let mut nucleo = nucleo::Nucleo::new(nucleo::Config::DEFAULT, Arc::new(|| {}), Some(1), 1);
+        let injector = nucleo.injector();
+        for n in 0..2 * STICKY_SEARCH_LIMIT {
+            injector.push(n, |n, cols| cols[0] = n.to_string().into());
+        }
+        while nucleo.tick(10).running {}
+        let snapshot = nucleo.snapshot();
+        let address =
+            |index| ptr::from_ref(snapshot.get_matched_item(index).unwrap().data) as usize;
+
+        // items near the previous selection are found
+        assert_eq!(find_tracked(snapshot, address(105), 100, 10), Some(105));
+        assert_eq!(find_tracked(snapshot, address(0), 5, 10), Some(0));
+
+        // items far from the previous selection are found if they are near the start
+        assert_eq!(find_tracked(snapshot, address(500), 100, 10), Some(500));
+        let far = STICKY_SEARCH_LIMIT + 500;
+        assert_eq!(find_tracked(snapshot, address(far), 100, 10), None);
+        assert_eq!(find_tracked(snapshot, address(far), far - 5, 10), Some(far));
+
+        // items which are not matched are not found
+        assert_eq!(find_tracked(snapshot, 0, 100, 10), None);
+    }
+
+    /// Items which are replaced by shorter items with the same matches.
+    fn replacement_items(round: &mut usize) -> [&'static str; 2] {
+        *round += 1;
+        if *round == 1 {
+            ["xxxxxa", "yyyyya"]
+        } else {
+            ["a", "ab"]
+        }
+    }
+
+    /// Restart the picker several times with the same query, checking that the state of the
+    /// compositor for the removed items is not used for the new items.
+    fn assert_restarts_reset(
+        picker: &mut Picker<&'static str, StrRenderer>,
+        mut restart: impl FnMut(&mut Picker<&'static str, StrRenderer>),
+    ) {
+        let config = PickerConfig::default();
+        let mut term = Compositor::new((40, 10), 0, &config);
+        let mut buffer = CompositorBuffer::new();
+        let mut matcher = Matcher::default();
+        picker
+            .matcher
+            .pattern
+            .reparse(0, "a", CaseMatching::Smart, Normalization::Smart, false);
+
+        for _ in 0..3 {
+            restart(picker);
+            loop {
+                let status = picker.matcher.tick(10);
+                term.update(status, picker.matcher.snapshot(), false, picker.generation);
+                if !status.running {
+                    break;
+                }
+            }
+
+            // none of the new items are treated as previously drawn
+            assert!(term.drawn.is_empty());
+            term.draw(
+                &mut Vec::new(),
+                &mut matcher,
+                &StrRenderer,
+                picker.matcher.snapshot(),
+                &mut buffer,
+            )
+            .unwrap();
+
+            // the indices of the removed items are not used, since new items may have the same
+            // addresses
+            assert!(buffer.cached_indices.is_empty());
+            assert_eq!(buffer.frame_indices.len(), 2);
+            assert_eq!(term.drawn.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_reload_same_query() {
+        let mut picker: Picker<&str, _> = Picker::new(StrRenderer);
+        let mut round = 0;
+        picker.set_reload(move |mut injector| injector.extend(replacement_items(&mut round)));
+        assert_restarts_reset(&mut picker, |picker| {
+            picker.reload();
+        });
+    }
+
+    #[test]
+    fn test_query_source_same_query() {
+        let mut picker: Picker<&str, _> = Picker::new(StrRenderer);
+        let mut round = 0;
+        picker.set_query_source(Duration::ZERO, move |_, mut injector| {
+            injector.extend(replacement_items(&mut round));
+        });
+        assert_restarts_reset(&mut picker, |picker| picker.run_query_source("a"));
+    }
+
+    #[test]
+    fn test_reload_sticky_selection() {
+        let mut picker: Picker<&str, _> = Picker::new(StrRenderer);
+        picker.set_reload(|mut injector| injector.extend(["foo", "bar", "baz"]));
+        let config = PickerConfig {
+            sticky_selection: true,
+            ..PickerConfig::default()
+        };
+        let mut term = Compositor::new((40, 10), 0, &config);
+
+        picker.reload();
+        while picker.matcher.tick(10).running {}
+        let changed = nucleo::Status {
+            changed: true,
+            running: false,
+        };
+        term.update(changed, picker.matcher.snapshot(), false, picker.generation);
+        term.set_selection(2);
+        term.track(picker.matcher.snapshot());
+        assert!(term.tracked.is_some());
+
+        // the selected item is removed by the reload, so the selection must not follow a new item
+        // which is added at the same address
+        picker.reload();
+        let unchanged = nucleo::Status {
+            changed: false,
+            running: true,
+        };
+        term.update(
+            unchanged,
+            picker.matcher.snapshot(),
+            false,
+            picker.generation,
+        );
+        assert!(term.tracked.is_none());
+    }
+
+    #[test]
+    fn test_exact_query() {
+        assert_eq!(exact_query(""), "");
+        assert_eq!(exact_query("foo"), "'foo");
+        assert_eq!(exact_query("foo bar"), "'foo 'bar");
+        assert_eq!(exact_query("foo  bar "), "'foo  'bar ");
+        assert_eq!(exact_query("'foo"), "foo");
+        assert_eq!(exact_query("^foo !bar baz$"), "^foo !bar baz$");
+        assert_eq!(exact_query("\\^foo \\'bar \\!baz"), "'^foo ''bar '!baz");
+        assert_eq!(exact_query("foo\\ bar"), "'foo\\ bar");
+    }
+
+    #[test]
+    fn test_indices_cache() {
+        let foo = Pattern::parse("foo", CaseMatching::Smart, Normalization::Smart);
+        let bar = Pattern::parse("bar", CaseMatching::Smart, Normalization::Smart);
+        let mut buffer = CompositorBuffer::new();
+
+        // indices drawn in a frame are kept for the next frame with the same pattern
+        buffer.start_frame(&foo, 0);
+        buffer.frame_indices.insert(1, vec![0, 1, 2]);
+        buffer.frame_indices.insert(2, vec![3, 4, 5]);
+        buffer.start_frame(&foo, 0);
+        assert_eq!(buffer.cached_indices.get(&1), Some(&vec![0, 1, 2]));
+
+        // indices which are not drawn in a frame are discarded
+        buffer
+            .frame_indices
+            .extend(buffer.cached_indices.remove(&1).map(|i| (1, i)));
+        buffer.start_frame(&foo, 0);
+        assert_eq!(buffer.cached_indices.len(), 1);
+        assert!(buffer.cached_indices.contains_key(&1));
+
+        // all indices are discarded if the pattern changes
+        buffer.frame_indices.insert(1, vec![0, 1, 2]);
+        buffer.start_frame(&bar, 0);
+        assert!(buffer.cached_indices.is_empty());
+
+        // all indices are discarded if the items change
+        buffer.frame_indices.insert(1, vec![0, 1, 2]);
+        buffer.start_frame(&bar, 1);
+        assert!(buffer.cached_indices.is_empty());
+    }
+
+    #[test]
+    fn test_next_case_matching() {
+        let mut case_matching = CaseMatching::Smart;
+        for expected in [
+            CaseMatching::Ignore,
+            CaseMatching::Respect,
+            CaseMatching::Smart,
+        ] {
+            case_matching = next_case_matching(case_matching);
+            assert_eq!(case_matching, expected);
+        }
+    }
+
+    #[test]
+    fn test_too_small() {
+        let mut config = PickerConfig::default();
+        let dimensions = Dimensions::from_screen(&config, 40, 10, 0);
+        assert!(!dimensions.is_too_small(None));
+        assert!(!dimensions.is_too_small(Some((40, 10))));
+        assert!(dimensions.is_too_small(Some((41, 10))));
+        assert!(dimensions.is_too_small(Some((40, 11))));
+
+        // the inline height is compared, rather than the screen height
+        config.inline_height = Some(5);
+        let dimensions = Dimensions::from_screen(&config, 40, 10, 3);
+        assert!(dimensions.is_too_small(Some((40, 6))));
+    }
+}
@@ -1,5 +1,9 @@
+use std::{borrow::Cow, ops::Range};
+
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use unicode_width::UnicodeWidthChar;
+
+use super::unicode::WidthPolicy;
 
 /// Mutate a given string in-place, removing ASCII control characters and converting newlines,
 /// carriage returns, and TABs to ASCII space.
@@ -11,6 +15,25 @@ pub fn normalize_query_string(s: &mut String) {
         .collect();
 }
 
+/// Remove control characters other than newlines from rendered text, and convert TABs to ASCII
+/// space. The text is only copied if it contains control characters.
+pub fn sanitize_rendered(s: &str) -> Cow<'_, str> {
+    if s.contains(|ch: char| ch.is_control() && ch != '\n') {
+        Cow::Owned(
+            s.chars()
+                .filter_map(|ch| match ch {
+                    '\n' => Some('\n'),
+                    '\t' => Some(' '),
+                    ch if ch.is_control() => None,
+                    ch => Some(ch),
+                })
+                .collect(),
+        )
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
 /// Normalize a single char, returning the resulting char as well as the width.
 ///
 /// This automaticlly removes control characters since `ch.width()` returns `None` for control
@@ -34,7 +57,7 @@ pub enum Edit {
     BackspaceWord,
     /// Delete a grapheme immediately following the current cursor position.
     Delete,
-    /// Paste a [`String`] at the current cursor position.
+    /// Paste a [`String`] at the current cursor position. Very long strings are truncated.
     Paste(String),
     /// Move the cursor left.
     Left,
@@ -52,6 +75,14 @@ pub enum Edit {
     ClearBefore,
     /// Delete everything after the cursor.
     ClearAfter,
+    /// Insert the most recently deleted text at the current cursor position.
+    Yank,
+    /// Replace the text inserted by the previous yank with the text deleted before it.
+    YankPop,
+    /// Undo the previous change.
+    Undo,
+    /// Redo the previously undone change.
+    Redo,
 }
 
 /// A movement to apply to an [`EditableString`].
@@ -71,6 +102,44 @@ enum CursorMovement {
     ToEnd,
 }
 
+/// The maximum number of deletions which are stored in the kill ring.
+const KILL_RING_CAPACITY: usize = 16;
+
+/// The maximum length, in bytes, of the contents after a paste. Anything beyond this is
+/// discarded, so that an accidental paste of a very large string does not freeze the picker.
+const MAX_PASTE_LEN: usize = 4096;
+
+/// Truncate the string to at most `max` bytes, without splitting a character. Returns whether
+/// or not the string was truncated.
+fn truncate_to_char_boundary(s: &mut String, max: usize) -> bool {
+    if s.len() > max {
+        let mut end = max;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        s.truncate(end);
+        true
+    } else {
+        false
+    }
+}
+
+/// The text inserted by a yank, which can be replaced by a yank-pop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Yanked {
+    /// The position of the inserted text in the contents.
+    range: Range<usize>,
+    /// The index of the inserted text in the kill ring.
+    index: usize,
+}
+
+/// The state of an [`EditableString`] which is restored by an undo or a redo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Revision {
+    contents: String,
+    offset: usize,
+}
+
 #[derive(Debug)]
 pub struct EditableString {
     contents: String,
@@ -79,6 +148,23 @@ pub struct EditableString {
     width: u16,
     left_padding: u16,
     right_padding: u16,
+    width_policy: WidthPolicy,
+    /// Text deleted by word or line deletions, with the most recent deletion last.
+    kill_ring: Vec<String>,
+    /// Whether or not the previous edit was a deletion, so that consecutive deletions are stored
+    /// as a single entry in the kill ring.
+    killing: bool,
+    /// The text inserted by the previous edit, if it was a yank or a yank-pop.
+    yanked: Option<Yanked>,
+    /// Previous states of the string, with the most recent state last.
+    undo_stack: Vec<Revision>,
+    /// States which were undone, with the most recently undone state last.
+    redo_stack: Vec<Revision>,
+    /// Whether or not the previous edit inserted a character, so that consecutive insertions can
+    /// be undone together.
+    inserting: bool,
+    /// Whether or not the previous edit was a paste which was truncated.
+    paste_truncated: bool,
 }
 
 impl EditableString {
@@ -92,9 +178,28 @@ impl EditableString {
             width,
             left_padding: prompt_padding,
             right_padding: prompt_padding,
+            width_policy: WidthPolicy::default(),
+            kill_ring: Vec::new(),
+            killing: false,
+            yanked: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            inserting: false,
+            paste_truncated: false,
         }
     }
 
+    /// Whether or not the previous edit was a paste which was truncated since the contents would
+    /// be longer than the maximum length.
+    pub fn paste_truncated(&self) -> bool {
+        self.paste_truncated
+    }
+
+    /// Set the policy used to compute the width of the contents.
+    pub fn set_width_policy(&mut self, width_policy: WidthPolicy) {
+        self.width_policy = width_policy;
+    }
+
     /// Whether or not the prompt is empty.
     pub fn is_empty(&self) -> bool {
         self.contents.is_empty()
@@ -108,7 +213,7 @@ impl EditableString {
         let (left_offset, extra) = loop {
             match left_indices.next() {
                 Some((offset, grapheme)) => {
-                    total_left_width += grapheme.width();
+                    total_left_width += self.width_policy.str_width(grapheme);
                     if total_left_width >= self.screen_offset.into() {
                         let extra = (total_left_width - self.screen_offset as usize) as u16;
                         break (
@@ -132,7 +237,7 @@ impl EditableString {
         let right_offset = loop {
             match right_indices.next() {
                 Some((offset, grapheme)) => {
-                    total_right_width += grapheme.width();
+                    total_right_width += self.width_policy.str_width(grapheme);
                     if total_right_width > max_right_width as usize {
                         break self.offset + offset;
                     }
@@ -177,19 +282,38 @@ impl EditableString {
             .min(self.width - self.right_padding);
     }
 
-    /// Insert a character at the cursor position.
-    fn insert_char(&mut self, ch: char, w: usize) -> bool {
-        self.contents.insert(self.offset, ch);
-        self.increase_by_width(w);
-        self.offset += ch.len_utf8();
-        true
-    }
-
     /// Insert a string at the cursor position.
+    ///
+    /// The inserted string may combine with the grapheme before the cursor, such as a combining
+    /// accent or a zero width joiner, or with the grapheme after the cursor, such as a base
+    /// character before a combining accent. Therefore the screen offset is updated using the
+    /// width of the grapheme clusters around the cursor, and the cursor is moved to the end of
+    /// the grapheme containing the end of the inserted string.
     fn insert(&mut self, string: &str) -> bool {
+        let start = self.contents[..self.offset]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(self.offset, |(offset, _)| offset);
+        let old_width = self
+            .width_policy
+            .str_width(&self.contents[start..self.offset]);
+
         self.contents.insert_str(self.offset, string);
-        self.increase_by_width(string.width());
-        self.offset += string.len();
+        let inserted_end = self.offset + string.len();
+        self.offset = self.contents[start..]
+            .grapheme_indices(true)
+            .map(|(offset, _)| start + offset)
+            .find(|offset| *offset >= inserted_end)
+            .unwrap_or(self.contents.len());
+
+        let new_width = self
+            .width_policy
+            .str_width(&self.contents[start..self.offset]);
+        if new_width >= old_width {
+            self.increase_by_width(new_width - old_width);
+        } else {
+            self.screen_offset = self.move_left(old_width - new_width);
+        }
         true
     }
 
@@ -201,7 +325,7 @@ impl EditableString {
         let left_padding = loop {
             match graphemes.next() {
                 Some(g) => {
-                    total_left_width += g.width();
+                    total_left_width += self.width_policy.str_width(g);
                     if total_left_width >= self.left_padding as usize {
                         break self.left_padding;
                     }
@@ -229,7 +353,7 @@ impl EditableString {
                 {
                     Some((new_offset, gp)) => {
                         self.offset = new_offset;
-                        self.screen_offset = self.move_left(gp.width());
+                        self.screen_offset = self.move_left(self.width_policy.str_width(gp));
                         true
                     }
                     None => false,
@@ -241,7 +365,9 @@ impl EditableString {
                     .next_back()
                 {
                     Some((new_offset, _)) => {
-                        let step_width = self.contents[new_offset..self.offset].width();
+                        let step_width = self
+                            .width_policy
+                            .str_width(&self.contents[new_offset..self.offset]);
                         self.offset = new_offset;
                         self.screen_offset = self.move_left(step_width);
                         true
@@ -252,7 +378,7 @@ impl EditableString {
             CursorMovement::Right => match self.contents[self.offset..].graphemes(true).next() {
                 Some(gp) => {
                     self.offset += gp.len();
-                    self.increase_by_width(gp.width());
+                    self.increase_by_width(self.width_policy.str_width(gp));
                     true
                 }
                 None => false,
@@ -264,7 +390,9 @@ impl EditableString {
                         .next()
                         .map(|(s, _)| self.offset + s)
                         .unwrap_or(self.contents.len());
-                    let step_width = self.contents[self.offset..next_offset].width();
+                    let step_width = self
+                        .width_policy
+                        .str_width(&self.contents[self.offset..next_offset]);
                     self.offset = next_offset;
                     self.increase_by_width(step_width);
                     true
@@ -287,9 +415,12 @@ impl EditableString {
                 } else {
                     let max_offset = self.width - self.right_padding;
                     for gp in self.contents[self.offset..].graphemes(true) {
-                        self.screen_offset = self
-                            .screen_offset
-                            .saturating_add(gp.width().try_into().unwrap_or(u16::MAX));
+                        self.screen_offset = self.screen_offset.saturating_add(
+                            self.width_policy
+                                .str_width(gp)
+                                .try_into()
+                                .unwrap_or(u16::MAX),
+                        );
                         if self.screen_offset >= max_offset {
                             self.screen_offset = max_offset;
                             break;
@@ -302,8 +433,131 @@ impl EditableString {
         }
     }
 
-    /// Edit the editable string according to the provided [`Edit`] action.
+    /// The current state, for the undo history.
+    fn revision(&self) -> Revision {
+        Revision {
+            contents: self.contents.clone(),
+            offset: self.offset,
+        }
+    }
+
+    /// Restore a state from the undo history, returning the current state.
+    fn restore(&mut self, revision: Revision) -> Revision {
+        let current = self.revision();
+        self.contents = revision.contents;
+        self.offset = revision.offset;
+        // place the cursor as far right as possible, as if it had moved there from the start
+        self.screen_offset = self
+            .width_policy
+            .str_width(&self.contents[..self.offset])
+            .try_into()
+            .unwrap_or(u16::MAX)
+            .min(self.width - self.right_padding);
+        current
+    }
+
+    /// Add deleted text to the kill ring. If the previous edit was also a deletion, the text is
+    /// added to the most recent entry instead, before the entry if the text was deleted
+    /// `backward` from the cursor.
+    fn kill(&mut self, killed: String, backward: bool) {
+        match self.kill_ring.last_mut() {
+            Some(last) if self.killing => {
+                if backward {
+                    last.insert_str(0, &killed);
+                } else {
+                    last.push_str(&killed);
+                }
+            }
+            _ => {
+                if self.kill_ring.len() == KILL_RING_CAPACITY {
+                    self.kill_ring.remove(0);
+                }
+                self.kill_ring.push(killed);
+            }
+        }
+    }
+
+    /// Insert the entry of the kill ring with the given index at the cursor.
+    fn yank(&mut self, index: usize) -> bool {
+        let killed = self.kill_ring[index].clone();
+        let start = self.offset;
+        self.insert(&killed);
+        self.yanked = Some(Yanked {
+            range: start..self.offset,
+            index,
+        });
+        true
+    }
+
+    /// Edit the editable string according to the provided [`Edit`] action, recording the change
+    /// in the undo history.
     pub fn edit(&mut self, e: Edit) -> bool {
+        self.paste_truncated = false;
+        let is_kill = matches!(
+            e,
+            Edit::BackspaceWord | Edit::ClearBefore | Edit::ClearAfter
+        );
+        let is_yank = matches!(e, Edit::Yank | Edit::YankPop);
+        let changed = self.edit_history(e);
+
+        // only consecutive deletions are combined, and only the text inserted by the previous
+        // edit can be replaced by a yank-pop
+        self.killing = is_kill && changed;
+        if !is_yank {
+            self.yanked = None;
+        }
+        changed
+    }
+
+    /// Apply the provided [`Edit`] action, recording the change in the undo history.
+    fn edit_history(&mut self, e: Edit) -> bool {
+        match e {
+            Edit::Undo => match self.undo_stack.pop() {
+                Some(revision) => {
+                    let current = self.restore(revision);
+                    self.redo_stack.push(current);
+                    self.inserting = false;
+                    true
+                }
+                None => false,
+            },
+            Edit::Redo => match self.redo_stack.pop() {
+                Some(revision) => {
+                    let current = self.restore(revision);
+                    self.undo_stack.push(current);
+                    self.inserting = false;
+                    true
+                }
+                None => false,
+            },
+            Edit::Left
+            | Edit::WordLeft
+            | Edit::Right
+            | Edit::WordRight
+            | Edit::ToStart
+            | Edit::ToEnd => {
+                self.inserting = false;
+                self.edit_inner(e)
+            }
+            _ => {
+                let is_insert = matches!(e, Edit::Insert(_));
+                let revision = self.revision();
+                let changed = self.edit_inner(e);
+                if changed {
+                    // consecutive insertions are undone together
+                    if !(is_insert && self.inserting) {
+                        self.undo_stack.push(revision);
+                    }
+                    self.redo_stack.clear();
+                }
+                self.inserting = is_insert;
+                changed
+            }
+        }
+    }
+
+    /// Apply the provided [`Edit`] action, without modifying the undo history.
+    fn edit_inner(&mut self, e: Edit) -> bool {
         match e {
             Edit::Left => self.move_cursor(CursorMovement::Left),
             Edit::WordLeft => self.move_cursor(CursorMovement::WordLeft),
@@ -312,15 +566,22 @@ impl EditableString {
             Edit::ToStart => self.move_cursor(CursorMovement::ToStart),
             Edit::ToEnd => self.move_cursor(CursorMovement::ToEnd),
             Edit::Insert(ch) => {
-                if let Some((ch, w)) = normalize_char(ch) {
-                    self.insert_char(ch, w)
+                if let Some((ch, _)) = normalize_char(ch) {
+                    self.insert(ch.encode_utf8(&mut [0; 4]))
                 } else {
                     false
                 }
             }
             Edit::Paste(mut s) => {
+                // truncate before normalizing, so that the time taken is bounded
+                let mut truncated = truncate_to_char_boundary(&mut s, 4 * MAX_PASTE_LEN);
                 normalize_query_string(&mut s);
-                self.insert(&s)
+                truncated |= truncate_to_char_boundary(
+                    &mut s,
+                    MAX_PASTE_LEN.saturating_sub(self.contents.len()),
+                );
+                self.paste_truncated = truncated;
+                !s.is_empty() && self.insert(&s)
             }
             Edit::Backspace => {
                 let delete_until = self.offset;
@@ -334,7 +595,8 @@ impl EditableString {
             Edit::BackspaceWord => {
                 let delete_until = self.offset;
                 if self.move_cursor(CursorMovement::WordLeft) {
-                    self.contents.replace_range(self.offset..delete_until, "");
+                    let killed = self.contents.drain(self.offset..delete_until).collect();
+                    self.kill(killed, true);
                     true
                 } else {
                     false
@@ -344,7 +606,8 @@ impl EditableString {
                 if self.offset == 0 {
                     false
                 } else {
-                    self.contents.replace_range(..self.offset, "");
+                    let killed = self.contents.drain(..self.offset).collect();
+                    self.kill(killed, true);
                     self.offset = 0;
                     self.screen_offset = 0;
                     true
@@ -362,10 +625,31 @@ impl EditableString {
                 if self.offset == self.contents.len() {
                     false
                 } else {
-                    self.contents.truncate(self.offset);
+                    let killed = self.contents.split_off(self.offset);
+                    self.kill(killed, false);
                     true
                 }
             }
+            Edit::Yank => match self.kill_ring.len() {
+                0 => false,
+                len => self.yank(len - 1),
+            },
+            Edit::YankPop => match self.yanked.take() {
+                Some(Yanked { range, index }) if self.kill_ring.len() > 1 => {
+                    let mut contents = self.contents.clone();
+                    contents.replace_range(range.clone(), "");
+                    self.restore(Revision {
+                        contents,
+                        offset: range.start,
+                    });
+                    // rotate to the previous entry, wrapping around to the most recent entry
+                    let index = index.checked_sub(1).unwrap_or(self.kill_ring.len() - 1);
+                    self.yank(index)
+                }
+                _ => false,
+            },
+            // the undo history is handled in `edit`
+            Edit::Undo | Edit::Redo => false,
         }
     }
 
@@ -533,6 +817,102 @@ mod tests {
         assert_eq!(editable.contents, "c");
     }
 
+    #[test]
+    fn test_yank() {
+        let mut editable = EditableString::new(20, 2);
+        assert!(!editable.edit(Edit::Yank));
+        editable.edit(Edit::Paste("foo bar baz".to_owned()));
+        editable.edit(Edit::BackspaceWord);
+        assert_eq!(editable.contents, "foo bar ");
+        editable.edit(Edit::ToStart);
+        editable.edit(Edit::Yank);
+        assert_eq!(editable.contents, "bazfoo bar ");
+        editable.edit(Edit::ClearAfter);
+        assert_eq!(editable.contents, "baz");
+        editable.edit(Edit::Yank);
+        editable.edit(Edit::Yank);
+        assert_eq!(editable.contents, "bazfoo bar foo bar ");
+        editable.edit(Edit::WordLeft);
+        editable.edit(Edit::ClearBefore);
+        assert_eq!(editable.contents, "bar ");
+        editable.edit(Edit::ToEnd);
+        editable.edit(Edit::Yank);
+        assert_eq!(editable.contents, "bar bazfoo bar foo ");
+    }
+
+    #[test]
+    fn test_yank_pop() {
+        let mut editable = EditableString::new(40, 2);
+        editable.edit(Edit::Paste("foo bar baz".to_owned()));
+
+        // consecutive deletions are yanked together
+        editable.edit(Edit::BackspaceWord);
+        editable.edit(Edit::BackspaceWord);
+        assert_eq!(editable.contents, "foo ");
+        editable.edit(Edit::Yank);
+        assert_eq!(editable.contents, "foo bar baz");
+        editable.edit(Edit::ToStart);
+        editable.edit(Edit::ClearAfter);
+        assert_eq!(editable.kill_ring, ["bar baz", "foo bar baz"]);
+
+        // a yank-pop replaces the yanked text with the previous deletion
+        editable.edit(Edit::Insert('x'));
+        assert!(!editable.edit(Edit::YankPop));
+        editable.edit(Edit::Yank);
+        assert_eq!(editable.contents, "xfoo bar baz");
+        assert!(editable.edit(Edit::YankPop));
+        assert_eq!(editable.contents, "xbar baz");
+        assert_eq!(editable.offset, editable.contents.len());
+        assert!(editable.edit(Edit::YankPop));
+        assert_eq!(editable.contents, "xfoo bar baz");
+
+        // only the text inserted by the previous edit can be replaced
+        editable.edit(Edit::Left);
+        assert!(!editable.edit(Edit::YankPop));
+        assert_eq!(editable.contents, "xfoo bar baz");
+    }
+
+    #[test]
+    fn test_undo() {
+        let mut editable = EditableString::new(20, 2);
+        assert!(!editable.edit(Edit::Undo));
+        assert!(!editable.edit(Edit::Redo));
+
+        // consecutive insertions are undone together
+        for ch in "ab".chars() {
+            editable.edit(Edit::Insert(ch));
+        }
+        editable.edit(Edit::Left);
+        editable.edit(Edit::Insert('c'));
+        editable.edit(Edit::Insert('d'));
+        assert_eq!(editable.contents, "acdb");
+        editable.edit(Edit::Backspace);
+        assert_eq!(editable.contents, "acb");
+
+        assert!(editable.edit(Edit::Undo));
+        assert_eq!(editable.contents, "acdb");
+        assert_eq!(editable.offset, 3);
+        assert_eq!(editable.screen_offset, 3);
+        assert!(editable.edit(Edit::Undo));
+        assert_eq!(editable.contents, "ab");
+        assert_eq!(editable.offset, 1);
+        assert!(editable.edit(Edit::Undo));
+        assert_eq!(editable.contents, "");
+        assert!(!editable.edit(Edit::Undo));
+
+        assert!(editable.edit(Edit::Redo));
+        assert_eq!(editable.contents, "ab");
+        assert!(editable.edit(Edit::Redo));
+        assert_eq!(editable.contents, "acdb");
+
+        // a new change clears the redo history
+        editable.edit(Edit::ClearAfter);
+        assert_eq!(editable.contents, "acd");
+        assert!(!editable.edit(Edit::Redo));
+        assert!(editable.edit(Edit::Undo));
+        assert_eq!(editable.contents, "acdb");
+    }
+
     #[test]
     fn test_delete() {
         let mut editable = EditableString::new(7, 2);
@@ -545,6 +925,38 @@ mod tests {
         assert_eq!(editable.screen_offset, 0);
     }
 
+    #[test]
+    fn test_width_policy() {
+        let mut editable = EditableString::new(10, 2);
+        editable.edit(Edit::Paste("a±".to_owned()));
+        editable.edit(Edit::Insert('±'));
+        assert_eq!(editable.screen_offset, 3);
+
+        let mut editable = EditableString::new(10, 2);
+        editable.set_width_policy(WidthPolicy::AmbiguousWide);
+        editable.edit(Edit::Paste("a±".to_owned()));
+        editable.edit(Edit::Insert('±'));
+        assert_eq!(editable.screen_offset, 5);
+        editable.edit(Edit::Left);
+        assert_eq!(editable.screen_offset, 3);
+
+        // woman + zero-width joiner + rocket
+        let mut editable = EditableString::new(10, 2);
+        editable.edit(Edit::Paste("👩\u{200d}🚀".to_owned()));
+        assert_eq!(editable.screen_offset, 2);
+
+        let mut editable = EditableString::new(10, 2);
+        editable.set_width_policy(WidthPolicy::Codepoint);
+        editable.edit(Edit::Paste("👩\u{200d}🚀".to_owned()));
+        assert_eq!(editable.screen_offset, 4);
+        let mut editable = EditableString::new(10, 2);
+        editable.set_width_policy(WidthPolicy::Custom(|grapheme| grapheme.chars().count()));
+        editable.edit(Edit::Paste("👩\u{200d}🚀".to_owned()));
+        assert_eq!(editable.screen_offset, 3);
+        editable.edit(Edit::Left);
+        assert_eq!(editable.screen_offset, 0);
+    }
+
     #[test]
     fn test_normalize_query() {
         let mut s = "a\nb".to_owned();
@@ -560,6 +972,13 @@ mod tests {
         assert_eq!(s, "a ｏ");
     }
 
+    #[test]
+    fn test_sanitize_rendered() {
+        assert!(matches!(sanitize_rendered("a\nｏ"), Cow::Borrowed("a\nｏ")));
+        assert_eq!(sanitize_rendered("a\tb\r\nc"), "a b\nc");
+        assert_eq!(sanitize_rendered("\u{1b}[31mｏ\u{1b}[0m"), "[31mｏ[0m");
+    }
+
     #[test]
     fn test_editable() {
         let mut editable = EditableString::new(3, 1);
@@ -630,4 +1049,125 @@ mod tests {
 
         assert_eq!(editable.contents, "aＡ");
     }
+
+    /// Apply the edits one at a time, checking that the cursor is always on a grapheme boundary
+    /// at the screen offset given by the width of the preceding contents.
+    fn check_cursor(editable: &mut EditableString, edits: impl IntoIterator<Item = Edit>) {
+        for e in edits {
+            editable.edit(e);
+            let before = &editable.contents[..editable.offset];
+            assert!(
+                editable
+                    .contents
+                    .grapheme_indices(true)
+                    .any(|(i, _)| i == editable.offset)
+                    || editable.offset == editable.contents.len(),
+                "cursor inside a grapheme of {:?}",
+                editable.contents
+            );
+            assert_eq!(
+                editable.screen_offset as usize,
+                editable.width_policy.str_width(before),
+                "screen offset of {:?} in {:?}",
+                before,
+                editable.contents
+            );
+        }
+    }
+
+    #[test]
+    fn test_combining_input() {
+        // a dead key followed by a combining accent
+        let mut editable = EditableString::new(20, 0);
+        check_cursor(&mut editable, [Edit::Insert('e'), Edit::Insert('\u{301}')]);
+        assert_eq!(editable.contents, "e\u{301}");
+        assert_eq!(editable.screen_offset, 1);
+        check_cursor(&mut editable, [Edit::Left, Edit::Right, Edit::Insert('a')]);
+        assert_eq!(editable.screen_offset, 2);
+
+        // a base character inserted before a combining accent joins with it
+        let mut editable = EditableString::new(20, 0);
+        check_cursor(
+            &mut editable,
+            [Edit::Insert('\u{301}'), Edit::ToStart, Edit::Insert('e')],
+        );
+        assert_eq!(editable.contents, "e\u{301}");
+        assert_eq!(editable.offset, editable.contents.len());
+
+        // composing Hangul jamo
+        let mut editable = EditableString::new(20, 0);
+        check_cursor(
+            &mut editable,
+            ['ᄒ', 'ᅡ', 'ᆫ', 'ᄀ', 'ᅳ', 'ᆯ'].map(Edit::Insert),
+        );
+        assert_eq!(editable.screen_offset, 4);
+        check_cursor(&mut editable, [Edit::Left, Edit::Left, Edit::ToEnd]);
+    }
+
+    #[test]
+    fn test_zero_width_joiner_input() {
+        let family = ['👨', '\u{200d}', '👩', '\u{200d}', '👧'];
+
+        for width_policy in [
+            WidthPolicy::Standard,
+            WidthPolicy::AmbiguousWide,
+            WidthPolicy::Codepoint,
+            WidthPolicy::Custom(|grapheme| grapheme.chars().count()),
+        ] {
+            let mut editable = EditableString::new(20, 0);
+            editable.set_width_policy(width_policy);
+            check_cursor(&mut editable, family.map(Edit::Insert));
+            check_cursor(&mut editable, [Edit::Insert('x'), Edit::Left, Edit::Left]);
+            assert_eq!(editable.offset, 0);
+            check_cursor(&mut editable, [Edit::Right, Edit::Insert('\u{200d}')]);
+            check_cursor(
+                &mut editable,
+                [Edit::Insert('👦'), Edit::Right, Edit::Backspace],
+            );
+            assert_eq!(editable.contents, "👨\u{200d}👩\u{200d}👧\u{200d}👦");
+        }
+
+        // the screen offset does not drift when the prompt is narrow
+        let mut editable = EditableString::new(6, 1);
+        for _ in 0..3 {
+            for e in family.map(Edit::Insert) {
+                editable.edit(e);
+                assert!(editable.screen_offset <= 5);
+            }
+        }
+        for _ in 0..3 {
+            editable.edit(Edit::Left);
+        }
+        assert_eq!(editable.offset, 0);
+        assert_eq!(editable.screen_offset, 0);
+    }
+
+    #[test]
+    fn test_paste_limit() {
+        let mut editable = EditableString::new(10, 1);
+        editable.edit(Edit::Paste("a".repeat(MAX_PASTE_LEN - 1)));
+        assert!(!editable.paste_truncated());
+        editable.edit(Edit::Paste("ＡＡ".to_owned()));
+        assert_eq!(editable.contents.len(), MAX_PASTE_LEN - 1);
+        assert!(editable.paste_truncated());
+
+        editable.edit(Edit::Paste("bc".to_owned()));
+        assert_eq!(editable.contents.len(), MAX_PASTE_LEN);
+        assert!(editable.contents.ends_with("ab"));
+        assert!(editable.paste_truncated());
+
+        // a full prompt does not accept a paste, but the truncation is still reported
+        assert!(!editable.edit(Edit::Paste("d".to_owned())));
+        assert!(editable.paste_truncated());
+
+        // the truncation is only reported until the next edit
+        editable.edit(Edit::Left);
+        assert!(!editable.paste_truncated());
+
+        let mut editable = EditableString::new(10, 1);
+        editable.edit(Edit::Paste("Ａ\n".repeat(10 * MAX_PASTE_LEN)));
+        assert!(editable.contents.len() <= MAX_PASTE_LEN);
+        assert!(editable.contents.starts_with("Ａ Ａ"));
+        assert!(editable.paste_truncated());
+    }
 }
@@ -1,8 +1,15 @@
+use std::borrow::Cow;
+
 use memchr::memchr_iter;
 use nucleo::{Item, Snapshot, Utf32Str};
 
-use super::VariableSizeBuffer;
-use crate::Render;
+use super::{
+    editable::sanitize_rendered,
+    item_trailer,
+    unicode::{with_processor, wrapped_rows, AsciiProcessor, Processor},
+    VariableSizeBuffer, WidthPolicy,
+};
+use crate::{injector::rendered_column, Render};
 
 impl<T: Send + Sync + 'static> VariableSizeBuffer for Snapshot<T> {
     type Cursor = u32;
@@ -16,8 +23,8 @@ impl<T: Send + Sync + 'static> VariableSizeBuffer for Snapshot<T> {
         self.matched_item_count()
     }
 
-    fn size(item: &Self::Item<'_>) -> usize {
-        let num_linebreaks = match item.matcher_columns[0].slice(..) {
+    fn size<'a>(&'a self, item: &Self::Item<'a>) -> usize {
+        let num_linebreaks = match rendered_column(&item.matcher_columns[0]) {
             Utf32Str::Ascii(bytes) => memchr_iter(b'\n', bytes).count(),
             Utf32Str::Unicode(chars) => {
                 // TODO: there is an upstream Unicode handling issue in that windows-style newlines are
@@ -45,25 +52,135 @@ impl<T: Send + Sync + 'static> VariableSizeBuffer for Snapshot<T> {
     }
 }
 
+/// A view into a [`Snapshot`] in which the rendered items are wrapped to a fixed width, so that
+/// the size of an item is the number of rows that it occupies after wrapping. The width of an
+/// item is reduced by the width of its trailer, if any.
+pub struct WrappedSnapshot<'a, T: Send + Sync + 'static, R> {
+    pub snapshot: &'a Snapshot<T>,
+    pub render: &'a R,
+    pub width: u16,
+    pub width_policy: WidthPolicy,
+    pub sanitize: bool,
+}
+
+/// The number of rows required to display `rendered` when wrapped to `width` columns.
+fn wrapped_item_rows<P: Processor>(processor: P, rendered: &str, width: u16) -> usize {
+    rendered
+        .split('\n')
+        .map(|line| {
+            wrapped_rows(
+                processor,
+                line.strip_suffix('\r').unwrap_or(line),
+                width as usize,
+            )
+        })
+        .sum()
+}
+
+impl<T: Send + Sync + 'static, R: Render<T>> VariableSizeBuffer for WrappedSnapshot<'_, T, R> {
+    type Cursor = u32;
+
+    type Item<'a>
+        = Item<'a, T>
+    where
+        Self: 'a;
+
+    fn count(&self) -> u32 {
+        self.snapshot.count()
+    }
+
+    fn size<'a>(&'a self, item: &Self::Item<'a>) -> usize {
+        let (_, width) = item_trailer(self.render, item.data, self.width_policy, self.width);
+        match RenderedItem::new(item, self.render, self.sanitize) {
+            RenderedItem::Ascii(s) => wrapped_item_rows(AsciiProcessor, s, width),
+            RenderedItem::Unicode(r) => with_processor!(self.width_policy, |processor| {
+                wrapped_item_rows(processor, r.as_ref(), width)
+            }),
+        }
+    }
+
+    fn before(&self, selection: Self::Cursor) -> impl DoubleEndedIterator<Item = Self::Item<'_>> {
+        self.snapshot.before(selection)
+    }
+
+    fn after(&self, selection: Self::Cursor) -> impl DoubleEndedIterator<Item = Self::Item<'_>> {
+        self.snapshot.after(selection)
+    }
+}
+
+/// A view into a buffer in which each item occupies at most `max_size` rows.
+pub struct Clamped<'a, B> {
+    pub buffer: &'a B,
+    pub max_size: usize,
+}
+
+impl<B: VariableSizeBuffer> VariableSizeBuffer for Clamped<'_, B> {
+    type Cursor = B::Cursor;
+
+    type Item<'a>
+        = B::Item<'a>
+    where
+        Self: 'a;
+
+    fn count(&self) -> u32 {
+        self.buffer.count()
+    }
+
+    fn size<'a>(&'a self, item: &Self::Item<'a>) -> usize {
+        self.buffer.size(item).min(self.max_size)
+    }
+
+    fn before(&self, cursor: Self::Cursor) -> impl DoubleEndedIterator<Item = Self::Item<'_>> {
+        self.buffer.before(cursor)
+    }
+
+    fn after(&self, cursor: Self::Cursor) -> impl DoubleEndedIterator<Item = Self::Item<'_>> {
+        self.buffer.after(cursor)
+    }
+}
+
+/// A rendered string, which is copied if control characters were removed from it.
+pub enum Sanitized<S> {
+    Rendered(S),
+    Owned(String),
+}
+
+impl<S: AsRef<str>> AsRef<str> for Sanitized<S> {
+    fn as_ref(&self) -> &str {
+        match self {
+            Sanitized::Rendered(s) => s.as_ref(),
+            Sanitized::Owned(s) => s,
+        }
+    }
+}
+
 /// A container type since a [`Render`] implementation might return a type which needs ownership.
 ///
 /// For the given item, check the corresponding variant. If the variant is ASCII, that means we can
 /// use much more efficient ASCII processing on rendering.
 pub enum RenderedItem<'a, S> {
     Ascii(&'a str),
-    Unicode(S),
+    Unicode(Sanitized<S>),
 }
 
-impl<'a, S> RenderedItem<'a, S> {
-    /// Initialize a new `RenderedItem` from an [`Item`] and a [`Render`] implementation.
-    pub fn new<T, R>(item: &Item<'a, T>, renderer: &R) -> Self
+impl<'a, S: AsRef<str>> RenderedItem<'a, S> {
+    /// Initialize a new `RenderedItem` from an [`Item`] and a [`Render`] implementation. If
+    /// `sanitize` is true, control characters are removed in the same way as when the item was
+    /// added to the picker.
+    pub fn new<T, R>(item: &Item<'a, T>, renderer: &R, sanitize: bool) -> Self
     where
         R: Render<T, Str<'a> = S>,
     {
-        if let Utf32Str::Ascii(bytes) = item.matcher_columns[0].slice(..) {
+        if let Utf32Str::Ascii(bytes) = rendered_column(&item.matcher_columns[0]) {
             RenderedItem::Ascii(unsafe { std::str::from_utf8_unchecked(bytes) })
         } else {
-            RenderedItem::Unicode(renderer.render(item.data))
+            let rendered = renderer.render(item.data);
+            if sanitize {
+                if let Cow::Owned(sanitized) = sanitize_rendered(rendered.as_ref()) {
+                    return RenderedItem::Unicode(Sanitized::Owned(sanitized));
+                }
+            }
+            RenderedItem::Unicode(Sanitized::Rendered(rendered))
         }
     }
 }
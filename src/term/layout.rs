@@ -46,7 +46,7 @@ pub trait VariableSizeBuffer {
     fn count(&self) -> u32;
 
     /// Compute the width of an item in the buffer.
-    fn size(item: &Self::Item<'_>) -> usize;
+    fn size<'a>(&'a self, item: &Self::Item<'a>) -> usize;
 
     /// An iterator over items below the cursor, iterating downwards.
     fn before(&self, cursor: Self::Cursor) -> impl DoubleEndedIterator<Item = Self::Item<'_>>;
@@ -57,13 +57,13 @@ pub trait VariableSizeBuffer {
     /// A convenience function to iterate over item sizes corresponding to items returned by
     /// [`below`](VariableSizeBuffer::below).
     fn sizes_before(&self, cursor: Self::Cursor) -> impl DoubleEndedIterator<Item = usize> {
-        self.before(cursor).map(|item| Self::size(&item))
+        self.before(cursor).map(|item| self.size(&item))
     }
 
     /// A convenience function to iterate over item sizes corresponding to items returned by
     /// [`above`](VariableSizeBuffer::above).
     fn sizes_after(&self, cursor: Self::Cursor) -> impl DoubleEndedIterator<Item = usize> {
-        self.after(cursor).map(|item| Self::size(&item))
+        self.after(cursor).map(|item| self.size(&item))
     }
 }
 
@@ -99,7 +99,7 @@ pub struct Layout {
 
 impl Layout {
     /// Get a representation of the current layout to be used for rendering.
-    fn view(&self) -> LayoutView {
+    fn view(&self) -> LayoutView<'_> {
         debug_assert!(self.below.iter().sum::<u16>() == self.screen_index + 1);
         LayoutView {
             below: &self.below,
@@ -164,7 +164,7 @@ impl Layout {
         padding_top: u16,
         cursor: <B as VariableSizeBuffer>::Cursor,
         buffer: &B,
-    ) -> LayoutView {
+    ) -> LayoutView<'_> {
         debug_assert!(padding_bottom + padding_top < total_size);
         debug_assert!(cursor.index() < buffer.count());
         self.clamp_indices(total_size, padding_top, buffer);
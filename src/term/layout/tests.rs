@@ -1,8 +1,45 @@
-use std::sync::Arc;
+use std::{borrow::Cow, sync::Arc};
 
 use nucleo::{Config, Nucleo, Utf32String};
 
 use super::*;
+use crate::{
+    render::StrRenderer,
+    term::{
+        item::{Clamped, WrappedSnapshot},
+        WidthPolicy,
+    },
+    Render,
+};
+
+/// A renderer which draws a trailer of width 4 after every item.
+struct TrailerRenderer;
+
+impl Render<&'static str> for TrailerRenderer {
+    type Str<'a> = &'a str;
+
+    fn render<'a>(&self, item: &'a &'static str) -> Self::Str<'a> {
+        item
+    }
+
+    fn trailer<'a>(&self, _: &'a &'static str) -> Option<Cow<'a, str>> {
+        Some(Cow::Borrowed("1234"))
+    }
+}
+
+fn wrapped<'a, R>(
+    snapshot: &'a nucleo::Snapshot<&'static str>,
+    render: &'a R,
+    width: u16,
+) -> WrappedSnapshot<'a, &'static str, R> {
+    WrappedSnapshot {
+        snapshot,
+        render,
+        width,
+        width_policy: WidthPolicy::Standard,
+        sanitize: false,
+    }
+}
 
 fn reset(nc: &mut Nucleo<&'static str>, items: &[&'static str]) {
     nc.restart(true);
@@ -626,3 +663,149 @@ fn test_layout_mid_screen() {
         }
     );
 }
+
+#[test]
+fn test_wrapped_trailer() {
+    let mut nc = Nucleo::new(Config::DEFAULT, Arc::new(|| {}), Some(1), 1);
+
+    reset(&mut nc, &["abcdefgh", "abcd"]);
+    let snapshot = nc.snapshot();
+    let item = snapshot.get_matched_item(0).unwrap();
+
+    // the trailer and the separating column leave 5 of the 10 columns to draw the item
+    assert_eq!(wrapped(snapshot, &StrRenderer, 10).size(&item), 1);
+    assert_eq!(wrapped(snapshot, &TrailerRenderer, 10).size(&item), 2);
+
+    // the layout reserves the rows in which the item is drawn next to the trailer
+    let mut layout = Layout::default();
+    assert_eq!(
+        layout.recompute(6, 0, 0, 0, &wrapped(snapshot, &TrailerRenderer, 10)),
+        LayoutView {
+            below: &[2],
+            above: &[1],
+        }
+    );
+}
+
+#[test]
+fn test_wrapped_size() {
+    let mut nc = Nucleo::new(Config::DEFAULT, Arc::new(|| {}), Some(1), 1);
+
+    reset(
+        &mut nc,
+        &["abcd", "abcdefghij", "ab\ncdefg", "ab\r\ncd", "", "日本語"],
+    );
+    let snapshot = nc.snapshot();
+    let wrapped = wrapped(snapshot, &StrRenderer, 4);
+    let sizes: Vec<usize> = snapshot
+        .matched_items(..)
+        .map(|item| wrapped.size(&item))
+        .collect();
+
+    // lines are wrapped separately, and wide characters are not split across rows
+    assert_eq!(sizes, [1, 3, 3, 2, 1, 2]);
+}
+
+#[test]
+fn test_clamped_size() {
+    let mut nc = Nucleo::new(Config::DEFAULT, Arc::new(|| {}), Some(1), 1);
+
+    reset(&mut nc, &["1", "1\n2", "1\n2\n3\n4", "abcdefghij"]);
+    let snapshot = nc.snapshot();
+    let clamped = Clamped {
+        buffer: snapshot,
+        max_size: 2,
+    };
+    let sizes: Vec<usize> = snapshot
+        .matched_items(..)
+        .map(|item| clamped.size(&item))
+        .collect();
+    assert_eq!(sizes, [1, 2, 2, 1]);
+
+    // the size after wrapping is clamped
+    let wrapped = wrapped(snapshot, &StrRenderer, 4);
+    let clamped = Clamped {
+        buffer: &wrapped,
+        max_size: 2,
+    };
+    let sizes: Vec<usize> = snapshot
+        .matched_items(..)
+        .map(|item| clamped.size(&item))
+        .collect();
+    assert_eq!(sizes, [1, 2, 2, 2]);
+}
+
+#[test]
+fn test_layout_wrapped() {
+    let mut nc = Nucleo::new(Config::DEFAULT, Arc::new(|| {}), Some(1), 1);
+
+    reset(&mut nc, &["abcdefghij"; 5]);
+    let snapshot = nc.snapshot();
+    let wrapped = wrapped(snapshot, &StrRenderer, 4);
+    let mut layout = Layout::default();
+
+    assert_eq!(
+        layout.recompute(7, 0, 0, 0, &wrapped),
+        LayoutView {
+            below: &[3],
+            above: &[3, 1],
+        }
+    );
+
+    // the selection remains visible when moving through the wrapped items
+    assert_eq!(
+        layout.recompute(7, 0, 0, 1, &wrapped),
+        LayoutView {
+            below: &[3, 3],
+            above: &[1],
+        }
+    );
+
+    assert_eq!(
+        layout.recompute(7, 0, 0, 4, &wrapped),
+        LayoutView {
+            below: &[3, 3, 1],
+            above: &[],
+        }
+    );
+}
+
+#[test]
+fn test_layout_wrapped_large() {
+    let mut nc = Nucleo::new(Config::DEFAULT, Arc::new(|| {}), Some(1), 1);
+
+    // the first item is taller than the screen after wrapping
+    reset(&mut nc, &["abcdefghijklmnopqrst", "ab"]);
+    let snapshot = nc.snapshot();
+    let wrapped = wrapped(snapshot, &StrRenderer, 4);
+    let mut layout = Layout::default();
+
+    assert_eq!(
+        layout.recompute(3, 0, 0, 0, &wrapped),
+        LayoutView {
+            below: &[3],
+            above: &[],
+        }
+    );
+
+    assert_eq!(
+        layout.recompute(3, 0, 0, 1, &wrapped),
+        LayoutView {
+            below: &[1, 2],
+            above: &[],
+        }
+    );
+
+    // a clamped item leaves space for the next item
+    let clamped = Clamped {
+        buffer: &wrapped,
+        max_size: 2,
+    };
+    assert_eq!(
+        layout.recompute(3, 0, 0, 0, &clamped),
+        LayoutView {
+            below: &[2],
+            above: &[1],
+        }
+    );
+}
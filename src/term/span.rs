@@ -1,7 +1,6 @@
 use std::{
     io::{self, Write},
     iter::once,
-    marker::PhantomData,
     ops::Range,
     slice::Iter,
 };
@@ -9,17 +8,59 @@ use std::{
 use crossterm::{
     cursor::{MoveToColumn, MoveToNextLine},
     style::{
-        Attribute, Color, Print, PrintStyledContent, SetAttribute, SetBackgroundColor, Stylize,
+        Attribute, ContentStyle, Print, PrintStyledContent, SetAttribute, SetStyle, StyledContent,
     },
     terminal::{Clear, ClearType},
     QueueableCommand,
 };
 
 use super::{
-    unicode::{consume, spans_from_indices, truncate, Processor, Span},
+    theme::Theme,
+    unicode::{bounded_width, consume, spans_from_indices, truncate, wrap_spans, Processor, Span},
     ELLIPSIS,
 };
 
+/// Which end of an item to truncate when it does not fit on the screen.
+///
+/// Regardless of the truncation policy, the item is never truncated so far that the leftmost
+/// highlighted character of the match is hidden.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Truncate {
+    /// Keep the start of the item visible and replace the end with an ellipsis.
+    #[default]
+    End,
+    /// Keep the end of the item visible and replace the start with an ellipsis. This is useful
+    /// for items such as file paths or URLs, where the end is usually the most relevant part.
+    Start,
+}
+
+/// The styles used to print the lines of an item.
+#[derive(Debug, Clone, Copy)]
+pub struct LineStyle<'a> {
+    /// Whether or not the item is selected.
+    pub selected: bool,
+    /// The style of the item, as determined by the renderer.
+    pub item: ContentStyle,
+    /// The styles used by the picker.
+    pub theme: &'a Theme,
+    /// Whether or not to leave space for an icon at the start of each line.
+    pub icon_column: bool,
+}
+
+impl LineStyle<'_> {
+    /// Set the style for the remainder of the line, which is the item style on top of the
+    /// selection style if the item is selected.
+    #[inline]
+    fn apply<W: Write>(self, stderr: &mut W) -> Result<(), io::Error> {
+        if self.selected {
+            stderr.queue(SetStyle(self.theme.selection))?;
+        }
+        stderr.queue(SetStyle(self.item))?;
+        Ok(())
+    }
+}
+
 /// An iterator over lines, as span slices.
 pub struct SpannedLines<'a> {
     iter: Iter<'a, Range<usize>>,
@@ -42,6 +83,32 @@ pub trait KeepLines {
     fn from_offset(offset: u16) -> Self;
 
     fn subslice<'a>(&self, lines: &'a [Range<usize>]) -> &'a [Range<usize>];
+
+    /// Whether or not the last of `len` lines is kept.
+    fn keeps_last(&self, len: usize) -> bool;
+}
+
+/// Keep lines from the first `max` lines only, as determined by `L`.
+pub struct Clamp<L> {
+    pub inner: L,
+    pub max: usize,
+}
+
+impl<L: KeepLines> KeepLines for Clamp<L> {
+    fn subslice<'a>(&self, lines: &'a [Range<usize>]) -> &'a [Range<usize>] {
+        self.inner.subslice(&lines[..lines.len().min(self.max)])
+    }
+
+    fn from_offset(offset: u16) -> Self {
+        Self {
+            inner: L::from_offset(offset),
+            max: usize::MAX,
+        }
+    }
+
+    fn keeps_last(&self, len: usize) -> bool {
+        self.inner.keeps_last(len.min(self.max))
+    }
 }
 
 pub struct Tail(usize);
@@ -54,6 +121,10 @@ impl KeepLines for Tail {
     fn from_offset(offset: u16) -> Self {
         Self(offset as usize)
     }
+
+    fn keeps_last(&self, _: usize) -> bool {
+        true
+    }
 }
 
 pub struct Head(usize);
@@ -66,10 +137,16 @@ impl KeepLines for Head {
     fn from_offset(offset: u16) -> Self {
         Self(offset as usize)
     }
+
+    fn keeps_last(&self, len: usize) -> bool {
+        self.0 >= len
+    }
 }
 
+#[cfg(test)]
 struct All;
 
+#[cfg(test)]
 impl KeepLines for All {
     fn subslice<'a>(&self, lines: &'a [Range<usize>]) -> &'a [Range<usize>] {
         lines
@@ -78,6 +155,10 @@ impl KeepLines for All {
     fn from_offset(_: u16) -> Self {
         Self
     }
+
+    fn keeps_last(&self, _: usize) -> bool {
+        true
+    }
 }
 
 /// Represent additional data on top of a string slice.
@@ -89,24 +170,47 @@ pub struct Spanned<'a, P> {
     rendered: &'a str,
     spans: &'a [Span],
     lines: &'a [Range<usize>],
-    _marker: PhantomData<P>,
+    processor: P,
 }
 
 impl<'a, P: Processor> Spanned<'a, P> {
     #[inline]
     pub fn new<L: KeepLines>(
+        processor: P,
         indices: &[u32],
         rendered: &'a str,
         spans: &'a mut Vec<Span>,
         lines: &'a mut Vec<Range<usize>>,
         keep_lines: L,
     ) -> Self {
-        spans_from_indices::<P>(indices, rendered, spans, lines);
+        spans_from_indices(processor, indices, rendered, spans, lines);
         Self {
             rendered,
             spans,
             lines: keep_lines.subslice(lines),
-            _marker: PhantomData,
+            processor,
+        }
+    }
+
+    /// Initialize with the lines wrapped to `width` columns, so that each line is a single row on
+    /// the screen. The lines to keep are selected from the wrapped rows.
+    #[inline]
+    pub fn new_wrapped<L: KeepLines>(
+        processor: P,
+        indices: &[u32],
+        rendered: &'a str,
+        spans: &'a mut Vec<Span>,
+        lines: &'a mut Vec<Range<usize>>,
+        keep_lines: L,
+        width: u16,
+    ) -> Self {
+        spans_from_indices(processor, indices, rendered, spans, lines);
+        wrap_spans(processor, rendered, spans, lines, width as usize);
+        Self {
+            rendered,
+            spans,
+            lines: keep_lines.subslice(lines),
+            processor,
         }
     }
 
@@ -124,82 +228,154 @@ impl<'a, P: Processor> Spanned<'a, P> {
         max_line_bytes
     }
 
-    /// Returns the width (possibly 0) required to render all of the spans which require highlighting.
+    /// Returns the width (possibly 0) required to render all of the spans which require
+    /// highlighting, or `None` if the width exceeds `bound`.
     #[inline]
-    fn required_width(&self) -> usize {
+    fn required_width(&self, bound: usize) -> Option<usize> {
         let mut required_width = 0;
 
         for line in self.lines() {
             // find the 'rightmost' highlighted span
             if let Some(span) = line.iter().rev().find(|span| span.is_match) {
-                required_width = required_width.max(
+                required_width = required_width.max(bounded_width(
+                    self.processor,
                     // spans[0] must exist since `find` returned something
-                    P::width(&self.rendered[line[0].range.start..span.range.end]),
-                );
+                    &self.rendered[line[0].range.start..span.range.end],
+                    bound,
+                )?);
             }
         }
-        required_width
+        Some(required_width)
+    }
+
+    /// Returns the minimal width (over all lines) of the non-highlighted prefix of each line, or
+    /// `None` if no line contains a highlighted span.
+    #[inline]
+    fn leftmost_highlight_width(&self) -> Option<usize> {
+        let mut leftmost: Option<usize> = None;
+
+        for line in self.lines() {
+            // find the 'leftmost' highlighted span.
+            if let Some(span) = line.iter().find(|span| span.is_match) {
+                let prefix = &self.rendered[line[0].range.start..span.range.start];
+                leftmost = match leftmost {
+                    // we only care about the width if it is smaller than the current minimum
+                    Some(current) => {
+                        Some(bounded_width(self.processor, prefix, current).unwrap_or(current))
+                    }
+                    None => Some(self.processor.width(prefix)),
+                };
+            }
+        }
+        leftmost
+    }
+
+    /// Compute the maximum width over all lines.
+    #[inline]
+    fn max_line_width(&self) -> usize {
+        self.lines()
+            .map(|line| match (line.first(), line.last()) {
+                (Some(first), Some(last)) => self
+                    .processor
+                    .width(&self.rendered[first.range.start..last.range.end]),
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0)
     }
 
     /// Returns the optiomal offset (in terminal columns) for printing the given line.
     /// The offset automatically reserves an extra space for a single indicator symbol (such as an
     /// ellipsis), if required. The ellipsis should be printed whenever the returned value is not
     /// `0`.
+    ///
+    /// Ideally, we would like to offset by enough to display the rightmost highlighted span; but we
+    /// prefer highlighting matches which are earlier in the string, so the offset never exceeds
+    /// the width before the leftmost highlighted span. In particular, we never need to compute
+    /// widths beyond the leftmost highlighted span and the width of the screen, which is important
+    /// for very long lines.
     #[inline]
     fn required_offset(&self, max_width: u16, highlight_padding: u16) -> usize {
-        match (self.required_width() + highlight_padding as usize).checked_sub(max_width as usize) {
-            None | Some(0) => 0,
-            Some(mut offset) => {
-                // ideally, we would like to offset by `offset`; but we prefer highlighting
-                // matches which are earlier in the string. Therefore, reduce `offset` so that it
-                // lies before the first highlighted character in each line.
-
-                let mut is_sharp = false; // if the offset cannot be increased because of a
-                                          // highlighted char early in the match
-
-                for line in self.lines() {
-                    // find the 'leftmost' highlighted span.
-                    if let Some(span) = line.iter().find(|span| span.is_match) {
-                        let no_highlight_width =
-                            P::width(&self.rendered[line[0].range.start..span.range.start]);
-                        if no_highlight_width <= offset {
-                            offset = no_highlight_width;
-                            is_sharp = true;
-                        }
-                    }
-                }
-
-                // if the offset is not sharp, reserve an extra space for the ellipsis symbol
-                if !is_sharp {
-                    offset += 1;
-                };
+        let Some(leftmost) = self.leftmost_highlight_width() else {
+            return 0;
+        };
 
-                // if the offset is exactly 1, set it to 0 since we can just print the first
-                // character instead of the ellipsis
-                if offset == 1 {
-                    0
-                } else {
-                    offset
+        // if the offset is sharp, it cannot be increased because of a highlighted char early in
+        // the match
+        let (mut offset, is_sharp) = match (leftmost + max_width as usize)
+            .checked_sub(highlight_padding as usize)
+            .and_then(|bound| self.required_width(bound))
+        {
+            None => (leftmost, true),
+            Some(required_width) => {
+                match (required_width + highlight_padding as usize).checked_sub(max_width as usize)
+                {
+                    None | Some(0) => return 0,
+                    Some(offset) => (offset, offset == leftmost),
                 }
             }
+        };
+
+        // if the offset is not sharp, reserve an extra space for the ellipsis symbol
+        if !is_sharp {
+            offset += 1;
+        };
+
+        // if the offset is exactly 1, set it to 0 since we can just print the first
+        // character instead of the ellipsis
+        if offset == 1 {
+            0
+        } else {
+            offset
+        }
+    }
+
+    /// Returns the offset (in terminal columns) required to display the end of the widest line,
+    /// including an extra space for the ellipsis symbol. The offset never exceeds the width before
+    /// the leftmost highlighted span, so that the highlighting remains visible.
+    #[inline]
+    fn tail_offset(&self, max_width: u16) -> usize {
+        let widest = self.max_line_width();
+
+        if widest <= max_width as usize {
+            return 0;
+        }
+
+        let offset = match self.leftmost_highlight_width() {
+            Some(leftmost) => (widest + 1 - max_width as usize).min(leftmost),
+            None => widest + 1 - max_width as usize,
+        };
+
+        // as in `required_offset`, we can just print the first character instead of the ellipsis
+        if offset == 1 {
+            0
+        } else {
+            offset
         }
     }
 
     /// Print the header for each line, which is either two spaces or styled indicator. This also
     /// sets the highlighting features for the given line.
     #[inline]
-    fn start_line<W: Write>(stderr: &mut W, selected: bool) -> Result<(), io::Error> {
-        if selected {
-            // print the line as bold, and with a 'selection' marker
+    fn start_line<W: Write>(stderr: &mut W, style: LineStyle<'_>) -> Result<(), io::Error> {
+        if style.icon_column {
+            // the icon is drawn afterwards, but we clear the column in case there is no icon
+            stderr.queue(Print("  "))?;
+        }
+        if style.selected {
+            // print the line with the selection style, and with a 'selection' marker; printing
+            // styled content may reset the line style, so we set it again afterwards
             stderr
-                .queue(SetAttribute(Attribute::Bold))?
-                .queue(SetBackgroundColor(Color::DarkGrey))?
-                .queue(PrintStyledContent("▌ ".magenta()))?;
+                .queue(SetStyle(style.theme.selection))?
+                .queue(PrintStyledContent(StyledContent::new(
+                    style.theme.selection_marker,
+                    "▌ ",
+                )))?;
         } else {
             // print a blank instead
             stderr.queue(Print("  "))?;
         }
-        Ok(())
+        style.apply(stderr)
     }
 
     /// Queue a string slice for printing to stderr, either highlighted or printed.
@@ -208,9 +384,15 @@ impl<'a, P: Processor> Spanned<'a, P> {
         stderr: &mut W,
         to_print: &str,
         highlight: bool,
+        style: LineStyle<'_>,
     ) -> Result<(), io::Error> {
         if highlight {
-            stderr.queue(PrintStyledContent(to_print.cyan()))?;
+            stderr.queue(PrintStyledContent(StyledContent::new(
+                style.theme.highlight,
+                to_print,
+            )))?;
+            // restore the line style, which may have been reset
+            style.apply(stderr)?;
         } else {
             stderr.queue(Print(to_print))?;
         }
@@ -230,14 +412,20 @@ impl<'a, P: Processor> Spanned<'a, P> {
 
     /// Print for display into a terminal with width `max_width`, and with styling to match if the
     /// item is selected or not.
+    ///
+    /// The lines are scrolled right by an additional `scroll` columns, but never so far that the
+    /// end of the widest line is no longer visible. If `scroll` is non-zero, this returns the
+    /// largest amount of scroll which has an effect on this item; otherwise, this returns `0`.
     #[inline]
     pub fn queue_print<W: Write>(
         &self,
         stderr: &mut W,
-        selected: bool,
+        style: LineStyle<'_>,
         max_width: u16,
         highlight_padding: u16,
-    ) -> Result<(), io::Error> {
+        truncate: Truncate,
+        scroll: usize,
+    ) -> Result<usize, io::Error> {
         if self.max_line_bytes() <= max_width.saturating_sub(highlight_padding) as usize {
             // Fast path: all of the lines are short, so we can just render them without any unicode width
             // checks. This should be the case for the majority of situations, unless the screen is
@@ -248,21 +436,47 @@ impl<'a, P: Processor> Spanned<'a, P> {
             // columns.
             //
             // If the input is ASCII, this check is optimal.
-            for line in self.lines() {
-                Self::start_line(stderr, selected)?;
-                for span in line {
-                    Self::print_span(stderr, self.index_in(span), span.is_match)?;
-                }
-                Self::finish_line(stderr)?;
-            }
+            self.queue_print_all(stderr, style)?;
+            Ok(0)
         } else {
-            let offset = self.required_offset(max_width, highlight_padding);
+            let mut offset = match truncate {
+                Truncate::End => self.required_offset(max_width, highlight_padding),
+                Truncate::Start => self.tail_offset(max_width),
+            };
+
+            // only compute the width of every line if the lines are scrolled
+            let max_scroll = if scroll > 0 {
+                let limit = (self.max_line_width() + 1).saturating_sub(max_width as usize);
+                let max_scroll = limit.saturating_sub(offset);
+                offset += scroll.min(max_scroll);
+                max_scroll
+            } else {
+                0
+            };
 
             for line in self.lines() {
-                Self::start_line(stderr, selected)?;
-                self.queue_print_line(stderr, line, offset, max_width)?;
+                Self::start_line(stderr, style)?;
+                self.queue_print_line(stderr, line, offset, max_width, style)?;
                 Self::finish_line(stderr)?;
             }
+            Ok(max_scroll)
+        }
+    }
+
+    /// Print every line in full, without any truncation. This is used when the lines are known to
+    /// fit on the screen, such as after wrapping with [`Spanned::new_wrapped`].
+    #[inline]
+    pub fn queue_print_all<W: Write>(
+        &self,
+        stderr: &mut W,
+        style: LineStyle<'_>,
+    ) -> Result<(), io::Error> {
+        for line in self.lines() {
+            Self::start_line(stderr, style)?;
+            for span in line {
+                Self::print_span(stderr, self.index_in(span), span.is_match, style)?;
+            }
+            Self::finish_line(stderr)?;
         }
         Ok(())
     }
@@ -276,6 +490,7 @@ impl<'a, P: Processor> Spanned<'a, P> {
         line: &[Span],
         offset: usize,
         capacity: u16,
+        style: LineStyle<'_>,
     ) -> Result<(), io::Error> {
         let mut remaining_capacity = capacity;
 
@@ -290,14 +505,31 @@ impl<'a, P: Processor> Spanned<'a, P> {
             stderr.queue(Print(ELLIPSIS))?;
         };
 
-        // consume as much of the first span as required to overtake the offset. since the width of
-        // the offset is bounded above by the width of the first span, this is guaranteed to occur
-        // within the first span
-        let first_span = &line[0];
-        let (init, alignment) = consume::<P>(self.index_in(first_span), offset);
-        let new_first_span = Span {
-            range: first_span.range.start + init..first_span.range.end,
-            is_match: first_span.is_match,
+        // skip the spans which are entirely hidden by the offset, and then consume as much of the
+        // next span as required to overtake the offset. the width of a span is only computed up to
+        // the remaining offset, so this is cheap even for very long spans
+        let mut spans = line.iter();
+        let mut remaining_offset = offset;
+        let (new_first_span, alignment) = loop {
+            let Some(span) = spans.next() else {
+                // the line is hidden entirely by the offset
+                return Ok(());
+            };
+
+            match bounded_width(self.processor, self.index_in(span), remaining_offset) {
+                Some(width) if remaining_offset > 0 => remaining_offset -= width,
+                _ => {
+                    let (init, alignment) =
+                        consume(self.processor, self.index_in(span), remaining_offset);
+                    break (
+                        Span {
+                            range: span.range.start + init..span.range.end,
+                            is_match: span.is_match,
+                        },
+                        alignment,
+                    );
+                }
+            }
         };
 
         // print the extra alignment characters
@@ -312,15 +544,15 @@ impl<'a, P: Processor> Spanned<'a, P> {
         }
 
         // print as many spans as possible
-        for span in once(&new_first_span).chain(line[1..].iter()) {
+        for span in once(&new_first_span).chain(spans) {
             let substr = self.index_in(span);
-            match truncate::<P>(substr, remaining_capacity) {
+            match truncate(self.processor, substr, remaining_capacity) {
                 Ok(new) => {
                     remaining_capacity = new;
-                    Self::print_span(stderr, substr, span.is_match)?;
+                    Self::print_span(stderr, substr, span.is_match, style)?;
                 }
                 Err((prefix, alignment)) => {
-                    Self::print_span(stderr, prefix, span.is_match)?;
+                    Self::print_span(stderr, prefix, span.is_match, style)?;
                     if alignment > 0 {
                         // there is already extra space; fill it
                         for _ in 0..alignment {
@@ -328,9 +560,9 @@ impl<'a, P: Processor> Spanned<'a, P> {
                         }
                     } else {
                         // overwrite the previous grapheme
-                        let undo_width = P::last_grapheme_width(
-                            &self.rendered[..span.range.start + prefix.len()],
-                        );
+                        let undo_width = self
+                            .processor
+                            .last_grapheme_width(&self.rendered[..span.range.start + prefix.len()]);
 
                         stderr.queue(MoveToColumn(2 + capacity - undo_width as u16))?;
                         for _ in 0..undo_width {
@@ -375,17 +607,29 @@ mod tests {
         fn assert_correct_width(indices: Vec<u32>, rendered: &str, expected_width: usize) {
             let mut spans = Vec::new();
             let mut lines = Vec::new();
-            let spanned: Spanned<'_, UnicodeProcessor> =
-                Spanned::new(&indices, rendered, &mut spans, &mut lines, All);
+            let spanned = Spanned::new(
+                UnicodeProcessor,
+                &indices,
+                rendered,
+                &mut spans,
+                &mut lines,
+                All,
+            );
 
             if is_unicode_safe(rendered) {
-                assert_eq!(spanned.required_width(), expected_width);
+                assert_eq!(spanned.required_width(usize::MAX), Some(expected_width));
             }
 
             if is_ascii_safe(rendered) {
-                let spanned: Spanned<'_, AsciiProcessor> =
-                    Spanned::new(&indices, rendered, &mut spans, &mut lines, All);
-                assert_eq!(spanned.required_width(), expected_width);
+                let spanned = Spanned::new(
+                    AsciiProcessor,
+                    &indices,
+                    rendered,
+                    &mut spans,
+                    &mut lines,
+                    All,
+                );
+                assert_eq!(spanned.required_width(usize::MAX), Some(expected_width));
             }
         }
 
@@ -401,29 +645,54 @@ mod tests {
         assert_correct_width(vec![1, 5], "ＨＨb\n\nab", 4);
     }
 
-    #[test]
-    fn test_required_offset() {
-        fn assert_correct_offset(
-            indices: Vec<u32>,
-            rendered: &str,
-            max_width: u16,
-            expected_offset: usize,
-        ) {
-            let mut spans = Vec::new();
-            let mut lines = Vec::new();
-
-            if is_unicode_safe(rendered) {
-                let spanned: Spanned<'_, UnicodeProcessor> =
-                    Spanned::new(&indices, rendered, &mut spans, &mut lines, All);
-                assert_eq!(spanned.required_offset(max_width, 0), expected_offset);
-            }
+    /// Check an offset method of [`Spanned`] with each processor which can render the text. The
+    /// method is passed once for each processor, since a closure cannot be generic.
+    fn assert_offset(
+        unicode_offset: impl Fn(&Spanned<'_, UnicodeProcessor>) -> usize,
+        ascii_offset: impl Fn(&Spanned<'_, AsciiProcessor>) -> usize,
+        indices: Vec<u32>,
+        rendered: &str,
+        expected_offset: usize,
+    ) {
+        let mut spans = Vec::new();
+        let mut lines = Vec::new();
+
+        if is_unicode_safe(rendered) {
+            let spanned = Spanned::new(
+                UnicodeProcessor,
+                &indices,
+                rendered,
+                &mut spans,
+                &mut lines,
+                All,
+            );
+            assert_eq!(unicode_offset(&spanned), expected_offset);
+        }
 
-            if is_ascii_safe(rendered) {
-                let spanned: Spanned<'_, AsciiProcessor> =
-                    Spanned::new(&indices, rendered, &mut spans, &mut lines, All);
-                assert_eq!(spanned.required_offset(max_width, 0), expected_offset);
-            }
+        if is_ascii_safe(rendered) {
+            let spanned = Spanned::new(
+                AsciiProcessor,
+                &indices,
+                rendered,
+                &mut spans,
+                &mut lines,
+                All,
+            );
+            assert_eq!(ascii_offset(&spanned), expected_offset);
         }
+    }
+
+    #[test]
+    fn test_required_offset() {
+        let assert_correct_offset = |indices, rendered, max_width, expected_offset| {
+            assert_offset(
+                |spanned| spanned.required_offset(max_width, 0),
+                |spanned| spanned.required_offset(max_width, 0),
+                indices,
+                rendered,
+                expected_offset,
+            );
+        };
 
         assert_correct_offset(vec![], "a", 1, 0);
         assert_correct_offset(vec![], "abc", 1, 0);
@@ -451,4 +720,58 @@ mod tests {
         assert_correct_offset(vec![2, 8], "abc\na\r\naＨd", 3, 2);
         assert_correct_offset(vec![2, 8], "abc\na\r\naＨd", 4, 0);
     }
+
+    #[test]
+    fn test_tail_offset() {
+        let assert_correct_offset = |indices, rendered, max_width, expected_offset| {
+            assert_offset(
+                |spanned| spanned.tail_offset(max_width),
+                |spanned| spanned.tail_offset(max_width),
+                indices,
+                rendered,
+                expected_offset,
+            );
+        };
+
+        assert_correct_offset(vec![], "abc", 3, 0);
+        assert_correct_offset(vec![], "abcd", 3, 2);
+        assert_correct_offset(vec![], "abcdef", 3, 4);
+        assert_correct_offset(vec![], "ab\nabcdef", 3, 4);
+        assert_correct_offset(vec![], "aＨＨ", 3, 3);
+
+        // the leftmost highlighted character remains visible
+        assert_correct_offset(vec![5], "abcdef", 3, 4);
+        assert_correct_offset(vec![2], "abcdef", 3, 2);
+        assert_correct_offset(vec![1], "abcdef", 3, 0);
+        assert_correct_offset(vec![0, 8], "ab\nabcdef", 3, 0);
+    }
+
+    #[test]
+    fn test_clamp() {
+        let lines = [0..1, 2..3, 4..5, 6..7];
+
+        let head = Clamp {
+            inner: Head(2),
+            max: 3,
+        };
+        assert_eq!(head.subslice(&lines), &[0..1, 2..3]);
+        assert!(!head.keeps_last(lines.len()));
+
+        let head = Clamp {
+            inner: Head(3),
+            max: 3,
+        };
+        assert_eq!(head.subslice(&lines), &[0..1, 2..3, 4..5]);
+        assert!(head.keeps_last(lines.len()));
+
+        let tail = Clamp {
+            inner: Tail(2),
+            max: 3,
+        };
+        assert_eq!(tail.subslice(&lines), &[2..3, 4..5]);
+        assert!(tail.keeps_last(lines.len()));
+
+        let unclamped: Clamp<Head> = Clamp::from_offset(4);
+        assert_eq!(unclamped.subslice(&lines), &lines);
+    }
 }
@@ -0,0 +1,264 @@
+use std::env;
+
+use crossterm::style::{Attribute, Attributes, Color, ContentStyle, Stylize};
+
+/// The styles used to draw the elements of the picker.
+///
+/// Each element is styled with a [`ContentStyle`], which can set the foreground color, background
+/// color, and attributes such as bold or italic text. The [`Default`] implementation gives the
+/// default styles of the picker.
+///
+/// Legacy Windows consoles which do not support ANSI escape sequences cannot display attributes,
+/// so the attributes are removed from the theme on such consoles.
+///
+/// ## Example
+/// ```
+/// use nucleo_picker::{
+///     crossterm::style::{ContentStyle, Stylize},
+///     render::StrRenderer,
+///     Picker, PickerOptions, Theme,
+/// };
+///
+/// let mut theme = Theme::default();
+/// theme.highlight = ContentStyle::new().red().underlined();
+/// theme.prompt = ContentStyle::new().blue().bold();
+///
+/// let picker: Picker<String, _> = PickerOptions::new().theme(theme).picker(StrRenderer);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Theme {
+    /// The style of the entire line of the selected item. By default, bold with a dark grey
+    /// background.
+    pub selection: ContentStyle,
+    /// The style of the marker (`▌`) next to the selected item, which is drawn on top of the
+    /// `selection` style. By default, magenta.
+    pub selection_marker: ContentStyle,
+    /// The style of the characters matched by the query. By default, cyan.
+    pub highlight: ContentStyle,
+    /// The style of the prompt prefix, such as `> `. By default, unstyled.
+    pub prompt: ContentStyle,
+    /// The style of the placeholder text displayed when the prompt is empty. By default, dim.
+    pub placeholder: ContentStyle,
+    /// The style of the match counter, as well as the overflow indicators. By default, italic
+    /// and green.
+    pub counter: ContentStyle,
+    /// The style of the [`Scrollbar`], if enabled. By default, dark grey.
+    pub scrollbar: ContentStyle,
+    /// The style of the text displayed at the right edge of each item, given by
+    /// [`Render::trailer`](crate::Render::trailer). By default, dim.
+    pub trailer: ContentStyle,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            selection: ContentStyle::new()
+                .on(Color::DarkGrey)
+                .attribute(Attribute::Bold),
+            selection_marker: ContentStyle::new().magenta(),
+            highlight: ContentStyle::new().cyan(),
+            prompt: ContentStyle::new(),
+            placeholder: ContentStyle::new().attribute(Attribute::Dim),
+            counter: ContentStyle::new().green().italic(),
+            scrollbar: ContentStyle::new().dark_grey(),
+            trailer: ContentStyle::new().attribute(Attribute::Dim),
+        }
+    }
+}
+
+impl Theme {
+    /// Remove the colors from every style, so that the theme only uses attributes such as bold
+    /// text. The selection is drawn in reverse video and the highlighted characters are bold and
+    /// underlined if they were previously distinguished by color.
+    pub(crate) fn without_color(&self) -> Self {
+        Self {
+            selection: strip_color(self.selection, Attribute::Reverse.into()),
+            selection_marker: strip_color(self.selection_marker, Attributes::none()),
+            highlight: strip_color(
+                self.highlight,
+                Attributes::from(Attribute::Bold) | Attribute::Underlined,
+            ),
+            prompt: strip_color(self.prompt, Attributes::none()),
+            placeholder: strip_color(self.placeholder, Attributes::none()),
+            counter: strip_color(self.counter, Attributes::none()),
+            scrollbar: strip_color(self.scrollbar, Attributes::none()),
+            trailer: strip_color(self.trailer, Attributes::none()),
+        }
+    }
+
+    /// Remove the attributes from every style, for consoles which only support colors. Styles
+    /// which were only distinguished by attributes are given a color instead.
+    pub(crate) fn without_attributes(&self) -> Self {
+        let dim = ContentStyle::new().dark_grey();
+        Self {
+            selection: strip_attributes(self.selection, ContentStyle::new().on_dark_grey()),
+            selection_marker: strip_attributes(self.selection_marker, ContentStyle::new()),
+            highlight: strip_attributes(self.highlight, ContentStyle::new().cyan()),
+            prompt: strip_attributes(self.prompt, ContentStyle::new()),
+            placeholder: strip_attributes(self.placeholder, dim),
+            counter: strip_attributes(self.counter, ContentStyle::new()),
+            scrollbar: strip_attributes(self.scrollbar, ContentStyle::new()),
+            trailer: strip_attributes(self.trailer, dim),
+        }
+    }
+}
+
+/// Remove the attributes from a style, using the `fallback` style instead if the style only had
+/// attributes.
+fn strip_attributes(style: ContentStyle, fallback: ContentStyle) -> ContentStyle {
+    if style.foreground_color.is_none()
+        && style.background_color.is_none()
+        && style.attributes != Attributes::none()
+    {
+        fallback
+    } else {
+        ContentStyle {
+            foreground_color: style.foreground_color,
+            background_color: style.background_color,
+            ..ContentStyle::new()
+        }
+    }
+}
+
+/// Whether or not the output is a legacy Windows console which does not support ANSI escape
+/// sequences. Such consoles are drawn using the console API, which only supports colors.
+pub fn is_legacy_console() -> bool {
+    #[cfg(windows)]
+    {
+        !crossterm::ansi_support::supports_ansi()
+    }
+    #[cfg(not(windows))]
+    {
+        false
+    }
+}
+
+/// Remove the colors from a style, adding the `fallback` attributes if the style had a color.
+fn strip_color(style: ContentStyle, fallback: Attributes) -> ContentStyle {
+    let mut attributes = style.attributes;
+    if style.foreground_color.is_some()
+        || style.background_color.is_some()
+        || style.underline_color.is_some()
+    {
+        attributes.extend(fallback);
+    }
+
+    ContentStyle {
+        attributes,
+        ..ContentStyle::new()
+    }
+}
+
+/// Whether or not to draw the picker with colors.
+///
+/// If colors are disabled, the colors are removed from the [`Theme`], and the selection and the
+/// highlighted characters are instead distinguished by attributes such as bold text and reverse
+/// video.
+///
+/// ## Example
+/// ```
+/// use nucleo_picker::{render::StrRenderer, ColorMode, Picker, PickerOptions};
+///
+/// let picker: Picker<String, _> = PickerOptions::new()
+///     .color_mode(ColorMode::Never)
+///     .picker(StrRenderer);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ColorMode {
+    /// Use colors, unless the `NO_COLOR` environment variable is set to a non-empty value, as
+    /// described at <https://no-color.org>, or the terminal does not support colors since the
+    /// `TERM` environment variable is `dumb`.
+    #[default]
+    Auto,
+    /// Never use colors.
+    Never,
+}
+
+impl ColorMode {
+    /// Whether or not colors should be used.
+    pub(crate) fn use_color(self) -> bool {
+        match self {
+            Self::Auto => {
+                env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && env::var_os("TERM").is_none_or(|term| term != "dumb")
+            }
+            Self::Never => false,
+        }
+    }
+}
+
+/// The glyphs used to draw a scrollbar on the right edge of the match list.
+///
+/// The scrollbar is only drawn if there are more matches than fit on the screen. The thumb
+/// indicates the position of the visible matches within all of the matches, and the track fills
+/// the remaining space. The style of the scrollbar is set by [`Theme::scrollbar`].
+///
+/// ## Example
+/// ```
+/// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions, Scrollbar};
+///
+/// let picker: Picker<String, _> = PickerOptions::new()
+///     .scrollbar(Some(Scrollbar::new('█', '░')))
+///     .picker(StrRenderer);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Scrollbar {
+    /// The glyph indicating the visible matches.
+    pub thumb: char,
+    /// The glyph drawn in the remaining rows.
+    pub track: char,
+}
+
+impl Scrollbar {
+    /// A scrollbar with the given glyphs. The glyphs should be a single column wide.
+    #[must_use]
+    pub fn new(thumb: char, track: char) -> Self {
+        Self { thumb, track }
+    }
+}
+
+impl Default for Scrollbar {
+    fn default() -> Self {
+        Self::new('┃', '│')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_without_color() {
+        let theme = Theme::default().without_color();
+
+        assert_eq!(
+            theme.selection,
+            ContentStyle::new()
+                .attribute(Attribute::Bold)
+                .attribute(Attribute::Reverse)
+        );
+        assert_eq!(
+            theme.highlight,
+            ContentStyle::new()
+                .attribute(Attribute::Bold)
+                .attribute(Attribute::Underlined)
+        );
+        assert_eq!(theme.selection_marker, ContentStyle::new());
+        assert_eq!(theme.counter, ContentStyle::new().italic());
+        assert_eq!(theme.placeholder, Theme::default().placeholder);
+    }
+
+    #[test]
+    fn test_without_attributes() {
+        let theme = Theme::default().without_attributes();
+
+        assert_eq!(theme.selection, ContentStyle::new().on_dark_grey());
+        assert_eq!(theme.highlight, Theme::default().highlight);
+        assert_eq!(theme.counter, ContentStyle::new().green());
+        assert_eq!(theme.placeholder, ContentStyle::new().dark_grey());
+        assert_eq!(theme.prompt, ContentStyle::new());
+    }
+}
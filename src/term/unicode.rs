@@ -3,7 +3,7 @@
 #![allow(clippy::cast_possible_truncation)]
 #![allow(clippy::module_name_repetitions)]
 
-use std::{iter::repeat, ops::Range};
+use std::{iter::repeat_n, mem, ops::Range, ptr};
 
 use memchr::memchr_iter;
 
@@ -11,8 +11,11 @@ use memchr::memchr_iter;
 /// the [`UnicodeSegmentation`](`unicode_segmentation::UnicodeSegmentation`) and
 /// [`UnicodeWidthStr`](unicode_width::UnicodeWidthStr) traits.
 ///
-/// This abstraction is sealed and only has two implementations [`UnicodeProcessor`] and
-/// [`AsciiProcessor`].
+/// This abstraction is sealed and only has five implementations [`UnicodeProcessor`],
+/// [`AmbiguousWideProcessor`], [`CodepointProcessor`], [`CustomProcessor`], and
+/// [`AsciiProcessor`]. The [`AmbiguousWideProcessor`], [`CodepointProcessor`], and
+/// [`CustomProcessor`] are identical to the [`UnicodeProcessor`] except for the widths that they
+/// compute; see [`WidthPolicy`] for more detail.
 ///
 /// Note that a [`UnicodeProcessor`] **is not a generalization** of [`AsciiProcessor`]. In most
 /// situations, it is, but the one edge case is that the windows-style newline `\r\n` is treated as
@@ -25,29 +28,186 @@ use memchr::memchr_iter;
 /// is doing upstream: for a given `&str`, if the match object is [`nucleo::Utf32Str::Unicode`],
 /// we use [`UnicodeProcessor`], and if the match object is [`nucleo::Utf32Str::Ascii`], we use
 /// [`AsciiProcessor`].
-pub trait Processor: private::Sealed {
+pub trait Processor: Copy + private::Sealed {
     /// Compute the width (in terms of visible columns) of the input string.
     ///
     /// This method assumes that `input` is non-empty and does not contain newlines or carriage
     /// returns. If this is not the case, the returned value is undefined.
-    fn width(input: &str) -> usize;
+    fn width(self, input: &str) -> usize;
 
     /// Return an iterator over pairs `(offset, grapheme_width)` for the graphemes in `input`.
-    fn grapheme_index_widths(input: &str) -> impl Iterator<Item = (usize, usize)>;
+    fn grapheme_index_widths(self, input: &str) -> impl Iterator<Item = (usize, usize)>;
 
     /// Compute the width (in terms of visible columns) of the last grapheme.
     ///
     /// This method assumes that `input` is non-empty and does not contain a trailing newline. If
     /// this is not the case, the returned value is undefined.
-    fn last_grapheme_width(input: &str) -> usize;
+    fn last_grapheme_width(self, input: &str) -> usize;
 }
 
 mod private {
     pub trait Sealed {}
     impl Sealed for super::UnicodeProcessor {}
+    impl Sealed for super::AmbiguousWideProcessor {}
+    impl Sealed for super::CodepointProcessor {}
+    impl Sealed for super::CustomProcessor {}
     impl Sealed for super::AsciiProcessor {}
 }
 
+/// The policy used to compute the display width of text which is not ASCII.
+///
+/// Terminals do not always agree on the width of certain characters. In particular, the width of
+/// characters in the Unicode [East Asian Width](https://www.unicode.org/reports/tr11/) category
+/// 'Ambiguous' depends on the context: these characters are usually displayed with a single
+/// column, but many terminals can be configured to display them with two columns, which is
+/// common in CJK locales.
+///
+/// Terminals also disagree on the width of sequences of characters, such as emoji joined with a
+/// zero-width joiner or followed by a variation selector, depending on the version of Unicode
+/// that they implement. By default, such sequences are assigned the width defined by recent
+/// versions of Unicode, but many terminals instead display each character separately.
+///
+/// If the width policy does not match the behaviour of the terminal, highlighting and
+/// truncation of items may be misaligned.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum WidthPolicy {
+    /// Characters with ambiguous width occupy a single column.
+    #[default]
+    Standard,
+    /// Characters with ambiguous width occupy two columns.
+    AmbiguousWide,
+    /// The width of text is the sum of the widths of its characters, ignoring sequences such as
+    /// emoji joined with zero-width joiners. Characters with ambiguous width occupy a single
+    /// column.
+    Codepoint,
+    /// The width of text is the sum of the widths of its grapheme clusters, as computed by the
+    /// provided function. This can be used to match the width tables of a specific terminal.
+    ///
+    /// The function is never called on newlines or control characters. The width of a grapheme
+    /// cluster is capped at the number of bytes in its UTF-8 encoding, so that ASCII characters
+    /// always occupy a single column.
+    ///
+    /// ## Example
+    /// Treat every emoji sequence joined with zero-width joiners as a single wide character, and
+    /// otherwise use the standard widths.
+    /// ```
+    /// use nucleo_picker::{render::StrRenderer, Picker, PickerOptions, WidthPolicy};
+    /// use unicode_width::UnicodeWidthStr;
+    ///
+    /// fn grapheme_width(grapheme: &str) -> usize {
+    ///     if grapheme.contains('\u{200d}') {
+    ///         2
+    ///     } else {
+    ///         grapheme.width()
+    ///     }
+    /// }
+    ///
+    /// let picker: Picker<String, _> = PickerOptions::new()
+    ///     .width_policy(WidthPolicy::Custom(grapheme_width))
+    ///     .picker(StrRenderer);
+    /// ```
+    Custom(fn(&str) -> usize),
+}
+
+impl PartialEq for WidthPolicy {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Custom(f), Self::Custom(g)) => ptr::fn_addr_eq(*f, *g),
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for WidthPolicy {}
+
+impl WidthPolicy {
+    /// Compute the width of a string slice.
+    #[inline]
+    pub(crate) fn str_width(self, input: &str) -> usize {
+        match self {
+            Self::Standard => unicode_width::UnicodeWidthStr::width(input),
+            Self::AmbiguousWide => unicode_width::UnicodeWidthStr::width_cjk(input),
+            Self::Codepoint => codepoint_width(input),
+            Self::Custom(grapheme_width) => {
+                unicode_segmentation::UnicodeSegmentation::graphemes(input, true)
+                    .map(|grapheme| custom_width(grapheme_width, grapheme))
+                    .sum()
+            }
+        }
+    }
+
+    /// Truncate a string slice so that it fits in the given number of columns.
+    #[inline]
+    pub(crate) fn truncate(self, input: &str, width: usize) -> &str {
+        let mut remaining = width;
+        for (offset, ch) in input.char_indices() {
+            match remaining.checked_sub(self.char_width(ch).unwrap_or(0)) {
+                Some(new) => remaining = new,
+                None => return &input[..offset],
+            }
+        }
+        input
+    }
+
+    /// Compute the width of a char, returning `None` for control characters.
+    #[inline]
+    pub(crate) fn char_width(self, ch: char) -> Option<usize> {
+        match self {
+            Self::Standard => unicode_width::UnicodeWidthChar::width(ch),
+            Self::AmbiguousWide => unicode_width::UnicodeWidthChar::width_cjk(ch),
+            Self::Codepoint => unicode_width::UnicodeWidthChar::width(ch),
+            Self::Custom(grapheme_width) => unicode_width::UnicodeWidthChar::width(ch)
+                .map(|_| custom_width(grapheme_width, ch.encode_utf8(&mut [0; 4]))),
+        }
+    }
+}
+
+/// Evaluate an expression with the [`Processor`] which computes widths according to a
+/// [`WidthPolicy`], bound to the given name. The expression is compiled separately for each
+/// processor, so that the widths are computed without dynamic dispatch.
+macro_rules! with_processor {
+    ($policy:expr, |$processor:ident| $body:expr) => {
+        match $policy {
+            $crate::term::unicode::WidthPolicy::Standard => {
+                let $processor = $crate::term::unicode::UnicodeProcessor;
+                $body
+            }
+            $crate::term::unicode::WidthPolicy::AmbiguousWide => {
+                let $processor = $crate::term::unicode::AmbiguousWideProcessor;
+                $body
+            }
+            $crate::term::unicode::WidthPolicy::Codepoint => {
+                let $processor = $crate::term::unicode::CodepointProcessor;
+                $body
+            }
+            $crate::term::unicode::WidthPolicy::Custom(grapheme_width) => {
+                let $processor = $crate::term::unicode::CustomProcessor(grapheme_width);
+                $body
+            }
+        }
+    };
+}
+
+pub(crate) use with_processor;
+
+/// Compute the width of a grapheme with a custom width function. The width is capped at the
+/// number of bytes, since the other width computations assume that the number of bytes is an upper
+/// bound for the width.
+#[inline]
+fn custom_width(grapheme_width: fn(&str) -> usize, grapheme: &str) -> usize {
+    grapheme_width(grapheme).min(grapheme.len())
+}
+
+/// Compute the width of a string slice as the sum of the widths of its characters.
+#[inline]
+fn codepoint_width(input: &str) -> usize {
+    input
+        .chars()
+        .map(|ch| unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0))
+        .sum()
+}
+
 /// Whether or not a given string slice is safe to use with a [`UnicodeProcessor`].
 #[inline]
 pub(crate) fn is_unicode_safe(input: &str) -> bool {
@@ -61,12 +221,13 @@ pub(crate) fn is_ascii_safe(input: &str) -> bool {
 }
 
 /// A [`Processor`] which is safe to use on strings for which `is_ascii()` returns false.
+#[derive(Clone, Copy)]
 pub struct UnicodeProcessor;
 
 impl Processor for UnicodeProcessor {
     /// Do things properly and use [`UnicodeWidthStr`](unicode_width::UnicodeWidthStr).
     #[inline]
-    fn width(input: &str) -> usize {
+    fn width(self, input: &str) -> usize {
         debug_assert!(is_unicode_safe(input));
         unicode_width::UnicodeWidthStr::width(input)
     }
@@ -74,7 +235,7 @@ impl Processor for UnicodeProcessor {
     /// Do things properly and use
     /// [`UnicodeSegmentation`](unicode_segmentation::UnicodeSegmentation).
     #[inline]
-    fn grapheme_index_widths(input: &str) -> impl Iterator<Item = (usize, usize)> {
+    fn grapheme_index_widths(self, input: &str) -> impl Iterator<Item = (usize, usize)> {
         debug_assert!(is_unicode_safe(input));
         unicode_segmentation::UnicodeSegmentation::grapheme_indices(input, true)
             .map(|(offset, grapheme)| (offset, unicode_width::UnicodeWidthStr::width(grapheme)))
@@ -84,7 +245,7 @@ impl Processor for UnicodeProcessor {
     /// [`UnicodeSegmentation`](unicode_segmentation::UnicodeSegmentation) as well as
     /// [`UnicodeWidthStr`](unicode_width::UnicodeWidthStr).
     #[inline]
-    fn last_grapheme_width(input: &str) -> usize {
+    fn last_grapheme_width(self, input: &str) -> usize {
         debug_assert!(is_unicode_safe(input));
         unicode_segmentation::UnicodeSegmentation::graphemes(input, true)
             .next_back()
@@ -92,25 +253,112 @@ impl Processor for UnicodeProcessor {
     }
 }
 
+/// A [`Processor`] which is identical to the [`UnicodeProcessor`] except that characters with
+/// ambiguous width occupy two columns, corresponding to [`WidthPolicy::AmbiguousWide`].
+#[derive(Clone, Copy)]
+pub struct AmbiguousWideProcessor;
+
+impl Processor for AmbiguousWideProcessor {
+    #[inline]
+    fn width(self, input: &str) -> usize {
+        debug_assert!(is_unicode_safe(input));
+        unicode_width::UnicodeWidthStr::width_cjk(input)
+    }
+
+    #[inline]
+    fn grapheme_index_widths(self, input: &str) -> impl Iterator<Item = (usize, usize)> {
+        debug_assert!(is_unicode_safe(input));
+        unicode_segmentation::UnicodeSegmentation::grapheme_indices(input, true)
+            .map(|(offset, grapheme)| (offset, unicode_width::UnicodeWidthStr::width_cjk(grapheme)))
+    }
+
+    #[inline]
+    fn last_grapheme_width(self, input: &str) -> usize {
+        debug_assert!(is_unicode_safe(input));
+        unicode_segmentation::UnicodeSegmentation::graphemes(input, true)
+            .next_back()
+            .map_or(0, unicode_width::UnicodeWidthStr::width_cjk)
+    }
+}
+
+/// A [`Processor`] which is identical to the [`UnicodeProcessor`] except that the width of a string
+/// is the sum of the widths of its characters, corresponding to [`WidthPolicy::Codepoint`].
+#[derive(Clone, Copy)]
+pub struct CodepointProcessor;
+
+impl Processor for CodepointProcessor {
+    #[inline]
+    fn width(self, input: &str) -> usize {
+        debug_assert!(is_unicode_safe(input));
+        codepoint_width(input)
+    }
+
+    #[inline]
+    fn grapheme_index_widths(self, input: &str) -> impl Iterator<Item = (usize, usize)> {
+        debug_assert!(is_unicode_safe(input));
+        unicode_segmentation::UnicodeSegmentation::grapheme_indices(input, true)
+            .map(|(offset, grapheme)| (offset, codepoint_width(grapheme)))
+    }
+
+    #[inline]
+    fn last_grapheme_width(self, input: &str) -> usize {
+        debug_assert!(is_unicode_safe(input));
+        unicode_segmentation::UnicodeSegmentation::graphemes(input, true)
+            .next_back()
+            .map_or(0, codepoint_width)
+    }
+}
+
+/// A [`Processor`] which is identical to the [`UnicodeProcessor`] except that the width of each
+/// grapheme is computed by a function, corresponding to [`WidthPolicy::Custom`].
+#[derive(Clone, Copy)]
+pub struct CustomProcessor(pub fn(&str) -> usize);
+
+impl Processor for CustomProcessor {
+    #[inline]
+    fn width(self, input: &str) -> usize {
+        debug_assert!(is_unicode_safe(input));
+        unicode_segmentation::UnicodeSegmentation::graphemes(input, true)
+            .map(|grapheme| custom_width(self.0, grapheme))
+            .sum()
+    }
+
+    #[inline]
+    fn grapheme_index_widths(self, input: &str) -> impl Iterator<Item = (usize, usize)> {
+        debug_assert!(is_unicode_safe(input));
+        unicode_segmentation::UnicodeSegmentation::grapheme_indices(input, true)
+            .map(move |(offset, grapheme)| (offset, custom_width(self.0, grapheme)))
+    }
+
+    #[inline]
+    fn last_grapheme_width(self, input: &str) -> usize {
+        debug_assert!(is_unicode_safe(input));
+        unicode_segmentation::UnicodeSegmentation::graphemes(input, true)
+            .next_back()
+            .map_or(0, |grapheme| custom_width(self.0, grapheme))
+    }
+}
+
+#[derive(Clone, Copy)]
 pub struct AsciiProcessor;
 
 impl Processor for AsciiProcessor {
     /// Since we assume there are no carriage returns and no newlines, the width of a string is
     /// just the number of bytes.
     #[inline]
-    fn width(input: &str) -> usize {
+    fn width(self, input: &str) -> usize {
         debug_assert!(is_ascii_safe(input));
         input.len()
     }
 
     #[inline]
-    fn grapheme_index_widths(input: &str) -> impl Iterator<Item = (usize, usize)> {
+    fn grapheme_index_widths(self, input: &str) -> impl Iterator<Item = (usize, usize)> {
         debug_assert!(is_ascii_safe(input));
-        repeat(1).take(input.len()).enumerate()
+        repeat_n(1, input.len()).enumerate()
     }
 
     #[inline]
-    fn last_grapheme_width(input: &str) -> usize {
+    fn last_grapheme_width(self, input: &str) -> usize {
         debug_assert!(is_ascii_safe(input));
         1
     }
@@ -136,12 +384,19 @@ pub struct Span {
 /// terms of unicode width as computed by [`UnicodeWidthStr`], and therefore may be 0 even for
 /// non-empty string slices such as `\u{200b}`.
 #[inline]
-pub fn truncate<P: Processor>(input: &str, capacity: u16) -> Result<u16, (&str, usize)> {
-    if let Some(remaining) = (capacity as usize).checked_sub(P::width(input)) {
-        Ok(remaining as u16)
+pub fn truncate<P: Processor>(
+    processor: P,
+    input: &str,
+    capacity: u16,
+) -> Result<u16, (&str, usize)> {
+    // the number of bytes is an upper bound for the width, so we only need to iterate over
+    // graphemes if the input could be too wide; this avoids computing the width of very long
+    // inputs, which would mostly be discarded anyway
+    if input.len() <= capacity as usize {
+        Ok(capacity - processor.width(input) as u16)
     } else {
         let mut current_length = 0;
-        for (offset, grapheme_width) in P::grapheme_index_widths(input) {
+        for (offset, grapheme_width) in processor.grapheme_index_widths(input) {
             let next_length = current_length + grapheme_width;
             if next_length > capacity as usize {
                 return Err((&input[..offset], capacity as usize - current_length));
@@ -153,6 +408,102 @@ pub fn truncate<P: Processor>(input: &str, capacity: u16) -> Result<u16, (&str,
     }
 }
 
+/// Compute the width of `input`, or return `None` if the width exceeds `bound`.
+///
+/// This only processes the graphemes of `input` which are required to reach the bound, so it
+/// is cheap to call on very long inputs.
+#[inline]
+pub fn bounded_width<P: Processor>(processor: P, input: &str, bound: usize) -> Option<usize> {
+    if input.len() <= bound {
+        // the number of bytes is an upper bound for the width
+        Some(processor.width(input))
+    } else {
+        let mut width: usize = 0;
+        for (_, grapheme_width) in processor.grapheme_index_widths(input) {
+            width += grapheme_width;
+            if width > bound {
+                return None;
+            }
+        }
+        Some(width)
+    }
+}
+
+/// Compute the number of rows required to display a line of text when it is wrapped to `width`
+/// columns. Lines are wrapped at grapheme boundaries, and every row contains at least one
+/// grapheme, even if the grapheme is wider than `width`.
+///
+/// This must agree with the rows produced by [`wrap_spans`].
+#[inline]
+pub fn wrapped_rows<P: Processor>(processor: P, line: &str, width: usize) -> usize {
+    if line.len() <= width {
+        // the number of bytes is an upper bound for the width
+        return 1;
+    }
+
+    let mut rows = 1;
+    let mut row_width = 0;
+    for (_, grapheme_width) in processor.grapheme_index_widths(line) {
+        if row_width > 0 && row_width + grapheme_width > width {
+            rows += 1;
+            row_width = 0;
+        }
+        row_width += grapheme_width;
+    }
+    rows
+}
+
+/// Split the lines produced by [`spans_from_indices`] into rows which are at most `width` columns
+/// wide, splitting spans at grapheme boundaries where required. Afterwards, each range in `lines`
+/// corresponds to a single row.
+pub fn wrap_spans<P: Processor>(
+    processor: P,
+    rendered: &str,
+    spans: &mut Vec<Span>,
+    lines: &mut Vec<Range<usize>>,
+    width: usize,
+) {
+    let unwrapped_spans = std::mem::take(spans);
+    let unwrapped_lines = std::mem::take(lines);
+
+    for line in unwrapped_lines {
+        let mut row_start = spans.len();
+        let mut row_width = 0;
+
+        for span in &unwrapped_spans[line] {
+            let mut span_start = span.range.start;
+
+            for (offset, grapheme_width) in
+                processor.grapheme_index_widths(&rendered[span.range.clone()])
+            {
+                if row_width > 0 && row_width + grapheme_width > width {
+                    let split = span.range.start + offset;
+                    if split > span_start {
+                        spans.push(Span {
+                            range: span_start..split,
+                            is_match: span.is_match,
+                        });
+                    }
+                    lines.push(row_start..spans.len());
+                    row_start = spans.len();
+                    row_width = 0;
+                    span_start = split;
+                }
+                row_width += grapheme_width;
+            }
+
+            if span_start < span.range.end {
+                spans.push(Span {
+                    range: span_start..span.range.end,
+                    is_match: span.is_match,
+                });
+            }
+        }
+
+        lines.push(row_start..spans.len());
+    }
+}
+
 /// Consume a prefix consisting of entire graphemes from `input` until the total length of the
 /// consumed graphemes exceeds `offset`. Returns a pair `(idx, alignment)` where `idx` is the
 /// byte index of the first valid grapheme, and `alignment` is the number of extra columns
@@ -161,9 +512,9 @@ pub fn truncate<P: Processor>(input: &str, capacity: u16) -> Result<u16, (&str,
 /// Usually `alignment == 0`, but in the presence of (for instance) double-width characters such as
 /// `Ｈ` it could be larger.
 #[inline]
-pub fn consume<P: Processor>(input: &str, offset: usize) -> (usize, usize) {
+pub fn consume<P: Processor>(processor: P, input: &str, offset: usize) -> (usize, usize) {
     let mut initial_width: usize = 0;
-    for (idx, grapheme_width) in P::grapheme_index_widths(input) {
+    for (idx, grapheme_width) in processor.grapheme_index_widths(input) {
         match initial_width.checked_sub(offset) {
             Some(diff) => return (idx, diff),
             None => initial_width += grapheme_width,
@@ -180,6 +531,7 @@ pub fn consume<P: Processor>(input: &str, offset: usize) -> (usize, usize) {
 /// which line, `lines` consists of contiguous sub-slices of `spans`.
 #[inline]
 pub fn spans_from_indices<P: Processor>(
+    processor: P,
     indices: &[u32],
     rendered: &str,
     spans: &mut Vec<Span>,
@@ -188,7 +540,7 @@ pub fn spans_from_indices<P: Processor>(
     spans.clear();
     lines.clear();
 
-    let mut grapheme_index_iter = P::grapheme_index_widths(rendered);
+    let mut grapheme_index_iter = processor.grapheme_index_widths(rendered);
 
     let mut iter_step_count = 0; // how many graphemes we have consumed
     let mut start = 0; // the current offset position for the next block
@@ -315,14 +667,10 @@ impl Iterator for IndexSpans<'_> {
         let (left, right) = loop {
             self.cursor += 1;
             match self.indices.get(self.cursor) {
-                Some(next) => {
-                    if *next == last + 1 {
-                        last += 1;
-                    } else {
-                        break (first, last);
-                    }
+                Some(next) if *next == last + 1 => {
+                    last += 1;
                 }
-                None => {
+                _ => {
                     break (first, last);
                 }
             }
@@ -339,11 +687,11 @@ mod tests {
     fn test_consume_offset() {
         fn assert_consume(input: &str, w: usize, expected: (usize, usize)) {
             if is_unicode_safe(input) {
-                assert_eq!(consume::<UnicodeProcessor>(input, w), expected);
+                assert_eq!(consume(UnicodeProcessor, input, w), expected);
             }
 
             if is_ascii_safe(input) {
-                assert_eq!(consume::<AsciiProcessor>(input, w), expected);
+                assert_eq!(consume(AsciiProcessor, input, w), expected);
             }
         }
         assert_consume("ab", 3, (2, 0));
@@ -364,8 +712,9 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::single_range_in_vec_init)]
     fn test_spanned() {
-        fn assert_matching_vecs<T: std::fmt::Debug + PartialEq>(a: &Vec<T>, b: &Vec<T>) {
+        fn assert_matching_vecs<T: std::fmt::Debug + PartialEq>(a: &[T], b: &[T]) {
             for (u, v) in a.iter().zip(b.iter()) {
                 assert_eq!(u, v);
             }
@@ -381,13 +730,13 @@ mod tests {
             let mut lines = Vec::new();
 
             if is_unicode_safe(input) {
-                spans_from_indices::<UnicodeProcessor>(&indices, &input, &mut spans, &mut lines);
+                spans_from_indices(UnicodeProcessor, &indices, input, &mut spans, &mut lines);
                 assert_matching_vecs(&spans, &expected_spans);
                 assert_matching_vecs(&lines, &expected_lines);
             }
 
             if is_ascii_safe(input) {
-                spans_from_indices::<AsciiProcessor>(&indices, &input, &mut spans, &mut lines);
+                spans_from_indices(AsciiProcessor, &indices, input, &mut spans, &mut lines);
                 assert_matching_vecs(&spans, &expected_spans);
                 assert_matching_vecs(&lines, &expected_lines);
             }
@@ -556,10 +905,10 @@ mod tests {
     fn test_truncate_width() {
         fn assert_truncate(input: &str, w: u16, expected: Result<u16, (&str, usize)>) {
             if is_unicode_safe(input) {
-                assert_eq!(truncate::<UnicodeProcessor>(input, w), expected);
+                assert_eq!(truncate(UnicodeProcessor, input, w), expected);
             }
             if is_ascii_safe(input) {
-                assert_eq!(truncate::<AsciiProcessor>(input, w), expected);
+                assert_eq!(truncate(AsciiProcessor, input, w), expected);
             }
         }
 
@@ -581,4 +930,108 @@ mod tests {
         assert_truncate("aＨ", 3, Ok(0));
         assert_truncate("aＨ", 4, Ok(1));
     }
+
+    #[test]
+    fn test_custom_width() {
+        // every grapheme is two columns wide, except ASCII characters which are capped at a
+        // single column
+        let processor = CustomProcessor(|_| 2);
+        assert_eq!(processor.width("éüa"), 5);
+        assert_eq!(processor.width("e\u{301}a"), 3);
+        assert_eq!(truncate(processor, "éüa", 3), Err(("é", 1)));
+        assert_eq!(wrapped_rows(processor, "éüa", 4), 2);
+
+        let policy = WidthPolicy::Custom(|_| 2);
+        assert_eq!(policy.str_width("éüa"), 5);
+        assert_eq!(policy.char_width('a'), Some(1));
+        assert_eq!(policy.char_width('é'), Some(2));
+        assert_eq!(policy.char_width('\u{7}'), None);
+        assert_eq!(policy.truncate("éüa", 3), "é");
+        assert_ne!(policy, WidthPolicy::Standard);
+    }
+
+    #[test]
+    fn test_bounded_width() {
+        fn assert_bounded_width(input: &str, bound: usize, expected: Option<usize>) {
+            if is_unicode_safe(input) {
+                assert_eq!(bounded_width(UnicodeProcessor, input, bound), expected);
+            }
+            if is_ascii_safe(input) {
+                assert_eq!(bounded_width(AsciiProcessor, input, bound), expected);
+            }
+        }
+
+        assert_bounded_width("", 0, Some(0));
+        assert_bounded_width("ab", 1, None);
+        assert_bounded_width("ab", 2, Some(2));
+        assert_bounded_width("Ｈｅ", 3, None);
+        assert_bounded_width("Ｈｅ", 4, Some(4));
+        assert_bounded_width("Ｈｅ", 5, Some(4));
+        assert_bounded_width("aＨ", 2, None);
+        assert_bounded_width("aＨ", 3, Some(3));
+        assert_bounded_width(&"a".repeat(100_000), 10, None);
+    }
+
+    #[test]
+    fn test_wrapped_rows() {
+        assert_eq!(wrapped_rows(AsciiProcessor, "", 3), 1);
+        assert_eq!(wrapped_rows(AsciiProcessor, "abc", 3), 1);
+        assert_eq!(wrapped_rows(AsciiProcessor, "abcd", 3), 2);
+        assert_eq!(wrapped_rows(AsciiProcessor, "abcdefg", 3), 3);
+        assert_eq!(wrapped_rows(UnicodeProcessor, "Ｈｅｌ", 3), 3);
+        assert_eq!(wrapped_rows(UnicodeProcessor, "Ｈｅｌ", 4), 2);
+        assert_eq!(wrapped_rows(UnicodeProcessor, "aＨｅ", 4), 2);
+        // graphemes which are wider than the row still occupy a single row
+        assert_eq!(wrapped_rows(UnicodeProcessor, "Ｈｅ", 1), 2);
+    }
+
+    #[test]
+    fn test_wrap_spans() {
+        fn assert_wrap(input: &str, indices: Vec<u32>, width: usize, expected: Vec<Vec<&str>>) {
+            let mut spans = Vec::new();
+            let mut lines = Vec::new();
+            spans_from_indices(UnicodeProcessor, &indices, input, &mut spans, &mut lines);
+            wrap_spans(UnicodeProcessor, input, &mut spans, &mut lines, width);
+
+            let rows: Vec<Vec<&str>> = lines
+                .iter()
+                .map(|line| {
+                    spans[line.clone()]
+                        .iter()
+                        .map(|span| &input[span.range.clone()])
+                        .collect()
+                })
+                .collect();
+            assert_eq!(rows, expected);
+
+            // the number of rows agrees with `wrapped_rows`
+            let num_rows: usize = input
+                .split('\n')
+                .map(|line| wrapped_rows(UnicodeProcessor, line, width))
+                .sum();
+            assert_eq!(num_rows, lines.len());
+        }
+
+        assert_wrap("abc", vec![], 3, vec![vec!["abc"]]);
+        assert_wrap(
+            "abcdefg",
+            vec![],
+            3,
+            vec![vec!["abc"], vec!["def"], vec!["g"]],
+        );
+        assert_wrap(
+            "abcdefg",
+            vec![1, 2, 3],
+            3,
+            vec![vec!["a", "bc"], vec!["d", "ef"], vec!["g"]],
+        );
+        assert_wrap(
+            "ab\ncdef",
+            vec![],
+            2,
+            vec![vec!["ab"], vec!["cd"], vec!["ef"]],
+        );
+        assert_wrap("a\n\nb", vec![], 2, vec![vec!["a"], vec![], vec!["b"]]);
+        assert_wrap("aＨｅ", vec![1], 2, vec![vec!["a"], vec!["Ｈ"], vec!["ｅ"]]);
+    }
 }